@@ -1,5 +1,31 @@
-export type PackageStatus = "current" | "outdated" | "unknown";
-export type PackageManager = "brew" | "npm" | "pip";
+export type PackageStatus = "current" | "ahead" | "outdated" | "unknown";
+export type PackageManager =
+  | "brew"
+  | "npm"
+  | "pip"
+  | "flatpak"
+  | "snap"
+  | "rustup"
+  | "mas"
+  | "composer"
+  | "dotnet_tool"
+  | "nix"
+  | "pacman"
+  | "zypper"
+  | "asdf"
+  | "macports"
+  | "opam"
+  | "choco"
+  | "gem"
+  | "cabal"
+  | "hex"
+  | "apk"
+  | "dnf"
+  | "luarocks"
+  | "vcpkg"
+  | "pnpm"
+  // Custom collectors report their own manager names.
+  | (string & {});
 
 export interface PackageRecord {
   name: string;
@@ -8,19 +34,33 @@ export interface PackageRecord {
   installed_at: string | null;
   status: PackageStatus;
   manager: PackageManager;
+  pinned: boolean;
+  dependency: boolean;
+  linked: boolean;
+  source: string | null;
+  homepage?: string | null;
+  deprecated?: string | null;
+  provenance?: string | null;
+  download_size?: number | null;
 }
 
 export interface InventorySnapshot {
   generated_at?: string | null;
   packages: PackageRecord[];
+  total?: number;
 }
 
 export interface CollectionWarning {
   manager: PackageManager;
   message: string;
+  count?: number;
 }
 
 export interface CollectionSummary {
   snapshot: InventorySnapshot;
   warnings: CollectionWarning[];
+  attempted_managers: PackageManager[];
+  raw_outputs?: Partial<Record<PackageManager, string[]>>;
+  durations: Partial<Record<PackageManager, { secs: number; nanos: number }>>;
+  cancelled?: boolean;
 }
@@ -0,0 +1,206 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use bagpack_core::{
+    check_against_manifest, collect_inventory_with, diff_snapshots, to_csv, to_table, write_json,
+    CollectInventoryOptions, CollectionSummary, ExportField, InventorySnapshot, Manifest,
+    PackageManager,
+};
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(name = "bagpack-cli", about = "Audit globally installed packages")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Collect packages and print them as a table.
+    Scan(CollectArgs),
+    /// Collect packages and print the snapshot as JSON.
+    Json(CollectArgs),
+    /// Collect packages and print the snapshot as CSV.
+    Csv(CollectArgs),
+    /// Compare two JSON snapshots.
+    Diff { old: PathBuf, new: PathBuf },
+    /// Collect the managers listed in a `bagpack.lock` manifest and report drift from it.
+    Check {
+        #[arg(default_value = "bagpack.lock")]
+        manifest: PathBuf,
+    },
+}
+
+#[derive(Debug, Args)]
+struct CollectArgs {
+    /// Manager to collect from; repeat to select several. Defaults to the platform defaults.
+    #[arg(long = "manager", value_name = "NAME")]
+    managers: Vec<PackageManager>,
+    /// Only include outdated packages.
+    #[arg(long)]
+    only_outdated: bool,
+    /// Exit with a non-zero status if any manager reported a warning.
+    #[arg(long)]
+    strict: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Commands::Scan(args) => collect(&args, |snapshot| {
+            print!("{}", to_table(snapshot));
+            Ok(())
+        }),
+        Commands::Json(args) => collect(&args, |snapshot| {
+            let mut stdout = io::stdout().lock();
+            write_json(snapshot, &mut stdout)?;
+            println!();
+            Ok(())
+        }),
+        Commands::Csv(args) => collect(&args, |snapshot| {
+            print!("{}", to_csv(snapshot, ExportField::DEFAULT));
+            Ok(())
+        }),
+        Commands::Diff { old, new } => diff(&old, &new),
+        Commands::Check { manifest } => check(&manifest),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn collect(
+    args: &CollectArgs,
+    print: impl FnOnce(&InventorySnapshot) -> io::Result<()>,
+) -> io::Result<ExitCode> {
+    let mut options = CollectInventoryOptions::default();
+    if !args.managers.is_empty() {
+        options.managers = args.managers.clone();
+    }
+
+    let CollectionSummary {
+        mut snapshot,
+        warnings,
+        ..
+    } = collect_inventory_with(&options);
+    if args.only_outdated {
+        snapshot = snapshot.only_outdated();
+    }
+
+    print(&snapshot)?;
+
+    for warning in &warnings {
+        eprintln!("warning: {}: {}", warning.manager.as_str(), warning.message);
+    }
+
+    if args.strict && !warnings.is_empty() {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn diff(old: &Path, new: &Path) -> io::Result<ExitCode> {
+    let diff = diff_snapshots(&read_snapshot(old)?, &read_snapshot(new)?);
+
+    for record in &diff.added {
+        println!(
+            "+ {} {} {}",
+            record.manager.as_str(),
+            record.name,
+            record.current_version
+        );
+    }
+    for record in &diff.removed {
+        println!(
+            "- {} {} {}",
+            record.manager.as_str(),
+            record.name,
+            record.current_version
+        );
+    }
+    for change in &diff.changed {
+        println!(
+            "~ {} {} {} -> {}",
+            change.manager.as_str(),
+            change.name,
+            change.from,
+            change.to
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn check(path: &Path) -> io::Result<ExitCode> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+    let manifest = Manifest::from_toml(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })?;
+
+    let options = CollectInventoryOptions {
+        managers: manifest.managers(),
+        ..CollectInventoryOptions::default()
+    };
+    let summary = collect_inventory_with(&options);
+    for warning in &summary.warnings {
+        eprintln!("warning: {}: {}", warning.manager.as_str(), warning.message);
+    }
+
+    let report = check_against_manifest(&summary.snapshot, &manifest);
+    for entry in &report.missing {
+        println!(
+            "- {} {} {}",
+            entry.manager.as_str(),
+            entry.name,
+            entry.version
+        );
+    }
+    for entry in &report.extra {
+        println!(
+            "+ {} {} {}",
+            entry.manager.as_str(),
+            entry.name,
+            entry.version
+        );
+    }
+    for mismatch in &report.mismatched {
+        println!(
+            "~ {} {} expected {}, installed {}",
+            mismatch.manager.as_str(),
+            mismatch.name,
+            mismatch.expected,
+            mismatch.installed
+        );
+    }
+
+    if report.is_clean() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn read_snapshot(path: &Path) -> io::Result<InventorySnapshot> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+    serde_json::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
@@ -0,0 +1,143 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+use bagpack_core::{demo_snapshot, write_json};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// Creates a directory holding fake manager executables, for use as the child's `PATH`.
+fn fake_bin(scripts: &[(&str, &str)]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for (name, body) in scripts {
+        write_script(dir.path(), name, body);
+    }
+    dir
+}
+
+fn write_script(dir: &Path, name: &str, body: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+const FAKE_BREW: &str = r#"case "$1" in
+  list) printf 'wget 1.24.5\njq 1.7.1\n' ;;
+  leaves) printf 'wget\njq\n' ;;
+  outdated) printf '{"formulae":[{"name":"wget","installed_versions":["1.24.5"],"current_version":"1.24.6"}]}' ;;
+esac"#;
+
+fn bagpack(path: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("bagpack-cli").unwrap();
+    cmd.env("PATH", path);
+    cmd
+}
+
+#[test]
+fn scan_brew_only_outdated_prints_table() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    bagpack(bin.path())
+        .args(["scan", "--manager", "brew", "--only-outdated"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("MANAGER  NAME"))
+        .stdout(predicate::str::contains(
+            "brew     wget  1.24.5   1.24.6  outdated",
+        ))
+        .stdout(predicate::str::contains("jq").not());
+}
+
+#[test]
+fn scan_strict_fails_when_a_manager_warns() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    bagpack(bin.path())
+        .args(["scan", "--manager", "brew", "--manager", "npm"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: npm"));
+
+    bagpack(bin.path())
+        .args(["scan", "--manager", "brew", "--manager", "npm", "--strict"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn json_and_csv_print_the_snapshot() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    let output = bagpack(bin.path())
+        .args(["json", "--manager", "brew"])
+        .output()
+        .unwrap();
+    let snapshot: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(snapshot["packages"].as_array().unwrap().len(), 2);
+
+    bagpack(bin.path())
+        .args(["csv", "--manager", "brew", "--only-outdated"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("manager,name,"))
+        .stdout(predicate::str::contains(
+            "brew,wget,1.24.5,1.24.6,,outdated,",
+        ));
+}
+
+#[test]
+fn diff_compares_two_snapshot_files() {
+    let dir = TempDir::new().unwrap();
+    let old_path = dir.path().join("old.json");
+    let new_path = dir.path().join("new.json");
+
+    let old = demo_snapshot();
+    let mut new = demo_snapshot();
+    new.packages[0].current_version = "1.24.6".into();
+    new.packages.pop();
+    write_json(&old, fs::File::create(&old_path).unwrap()).unwrap();
+    write_json(&new, fs::File::create(&new_path).unwrap()).unwrap();
+
+    Command::cargo_bin("bagpack-cli")
+        .unwrap()
+        .arg("diff")
+        .arg(&old_path)
+        .arg(&new_path)
+        .assert()
+        .success()
+        .stdout("- pip requests 2.32.3\n~ brew wget 1.24.5 -> 1.24.6\n");
+}
+
+#[test]
+fn unknown_manager_is_rejected() {
+    Command::cargo_bin("bagpack-cli")
+        .unwrap()
+        .args(["scan", "--manager", "apt-get"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown package manager"));
+}
+
+#[test]
+fn check_reports_manifest_drift() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+    let manifest = bin.path().join("bagpack.lock");
+    fs::write(
+        &manifest,
+        "[brew]\nwget = \"1.24.6\"\nripgrep = \"14.1.0\"\n",
+    )
+    .unwrap();
+
+    bagpack(bin.path())
+        .arg("check")
+        .arg(&manifest)
+        .assert()
+        .code(1)
+        .stdout(
+            "- brew ripgrep 14.1.0\n+ brew jq 1.7.1\n~ brew wget expected 1.24.6, installed 1.24.5\n",
+        );
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_apk(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "apk", &["list", "--installed"], None::<&[i32]>)?;
+    ensure_success(&list_output, "apk list --installed")?;
+
+    let installed = parse_installed(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let version_output = run_command(options, "apk", &["version", "-l", "<"], None::<&[i32]>)?;
+    ensure_success(&version_output, "apk version -l <")?;
+
+    let upgrades = parse_upgrades(&version_output.stdout);
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = upgrades.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Apk,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Splits an apk `name-version-rN` identifier such as `py3-setuptools-70.3.0-r0`.
+///
+/// Names may contain hyphens, so the version starts after the last hyphen that is followed by
+/// a digit, once the `-rN` package release is set aside.
+fn split_name_version(identifier: &str) -> Option<(&str, &str)> {
+    let without_release = match identifier.rsplit_once('-') {
+        Some((rest, release))
+            if release.strip_prefix('r').is_some_and(|digits| {
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+            }) =>
+        {
+            rest
+        }
+        _ => identifier,
+    };
+
+    let boundary = without_release
+        .rmatch_indices('-')
+        .map(|(index, _)| index)
+        .find(|&index| without_release[index + 1..].starts_with(|c: char| c.is_ascii_digit()))?;
+    let (name, version) = (&identifier[..boundary], &identifier[boundary + 1..]);
+    (!name.is_empty()).then_some((name, version))
+}
+
+/// Parses `apk list --installed` rows like
+/// `busybox-1.36.1-r15 x86_64 {busybox} (GPL-2.0-only) [installed]`.
+fn parse_installed(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let identifier = line.split_whitespace().next()?;
+            let (name, version) = split_name_version(identifier)?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Parses `apk version -l '<'` rows like `busybox-1.36.1-r15   < 1.36.1-r16`, skipping the
+/// `Installed: Available:` header.
+fn parse_upgrades(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (installed, latest) = line.split_once(" < ")?;
+            let (name, _) = split_name_version(installed.trim())?;
+            let latest = latest.split_whitespace().next()?;
+            Some((name.to_string(), latest.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_installed, parse_upgrades, split_name_version};
+
+    #[test]
+    fn apk_names_with_hyphens_split_at_version_boundary() {
+        assert_eq!(
+            split_name_version("py3-setuptools-70.3.0-r0"),
+            Some(("py3-setuptools", "70.3.0-r0"))
+        );
+        assert_eq!(
+            split_name_version("font-noto-cjk-0_git20220127-r1"),
+            Some(("font-noto-cjk", "0_git20220127-r1"))
+        );
+        assert_eq!(
+            split_name_version("libstdc++-13.2.1_git20231014-r0"),
+            Some(("libstdc++", "13.2.1_git20231014-r0"))
+        );
+        assert_eq!(split_name_version("busybox"), None);
+    }
+
+    #[test]
+    fn apk_list_and_version_output_parse() {
+        let installed = parse_installed(
+            "busybox-1.36.1-r15 x86_64 {busybox} (GPL-2.0-only) [installed]\n\
+             ca-certificates-bundle-20240226-r0 x86_64 {ca-certificates} (MPL-2.0 AND MIT) [installed]\n",
+        );
+        let upgrades = parse_upgrades(
+            "Installed:                                Available:\n\
+             busybox-1.36.1-r15                      < 1.36.1-r16\n",
+        );
+
+        assert_eq!(
+            installed,
+            vec![
+                ("busybox".to_string(), "1.36.1-r15".to_string()),
+                (
+                    "ca-certificates-bundle".to_string(),
+                    "20240226-r0".to_string()
+                ),
+            ]
+        );
+        assert_eq!(upgrades["busybox"], "1.36.1-r16");
+        assert_eq!(upgrades.len(), 1);
+    }
+}
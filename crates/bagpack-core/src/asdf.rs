@@ -0,0 +1,174 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{
+    compare_versions, ensure_success, run_command, CollectInventoryOptions, CollectionError,
+    PackageManager, PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_asdf(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    // asdf releases before 0.16 exit with 126 when any plugin has no version set.
+    let current_output = run_command(options, "asdf", &["current"], Some(&[126]))?;
+    ensure_success(&current_output, "asdf current")?;
+
+    let tools = parse_current(&current_output.stdout);
+
+    // A failing `asdf latest` (e.g. an offline plugin) only leaves that tool's status unknown.
+    let mut latest = HashMap::new();
+    for tool in &tools {
+        match run_command(options, "asdf", &["latest", &tool.plugin], None::<&[i32]>) {
+            Ok(output) => {
+                if let Some(version) = output.stdout.split_whitespace().next() {
+                    latest.insert(tool.plugin.clone(), version.to_string());
+                }
+            }
+            Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+            Err(_) => {}
+        }
+    }
+
+    Ok(build_records(tools, &latest))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct AsdfTool {
+    plugin: String,
+    version: String,
+    source: Option<String>,
+}
+
+/// Parses `asdf current` rows of `plugin version source`.
+///
+/// Releases from 0.16 print a `Name Version Source Installed` header and a trailing
+/// `true`/`false` column; older releases print no header and describe missing versions in the
+/// source column. Plugins without an installed version are skipped.
+fn parse_current(stdout: &str) -> Vec<AsdfTool> {
+    let mut has_installed_column = false;
+    let mut tools = Vec::new();
+
+    for line in stdout.lines() {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let [plugin, version, rest @ ..] = columns.as_slice() else {
+            continue;
+        };
+        if *plugin == "Name" && *version == "Version" {
+            has_installed_column = rest.last() == Some(&"Installed");
+            continue;
+        }
+        if version.starts_with('_')
+            || line.contains("No version is set")
+            || line.contains("Not installed")
+        {
+            continue;
+        }
+
+        let source = if has_installed_column {
+            match rest.split_last() {
+                Some((&"false", _)) => continue,
+                Some((_, source)) => source,
+                None => rest,
+            }
+        } else {
+            rest
+        };
+
+        tools.push(AsdfTool {
+            plugin: plugin.to_string(),
+            version: version.to_string(),
+            source: (!source.is_empty()).then(|| source.join(" ")),
+        });
+    }
+
+    tools
+}
+
+fn build_records(tools: Vec<AsdfTool>, latest: &HashMap<String, String>) -> Vec<PackageRecord> {
+    tools
+        .into_iter()
+        .map(|tool| {
+            let latest_version = latest.get(&tool.plugin).cloned();
+            let status = match &latest_version {
+                Some(latest) if compare_versions(latest, &tool.version) == Ordering::Greater => {
+                    PackageStatus::Outdated
+                }
+                Some(_) => PackageStatus::Current,
+                None => PackageStatus::Unknown,
+            };
+
+            PackageRecord {
+                name: tool.plugin,
+                current_version: tool.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Asdf,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: tool.source,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_current, AsdfTool};
+    use crate::PackageStatus;
+    use std::collections::HashMap;
+
+    #[test]
+    fn asdf_current_skips_plugins_without_versions() {
+        let stdout = "nodejs          20.11.0         /home/dev/.tool-versions\n\
+                      python          ______          No version is set. Run \"asdf <global|shell|local> python <version>\"\n\
+                      ruby            3.3.0           Not installed. Run \"asdf install ruby 3.3.0\"\n";
+
+        assert_eq!(
+            parse_current(stdout),
+            vec![AsdfTool {
+                plugin: "nodejs".into(),
+                version: "20.11.0".into(),
+                source: Some("/home/dev/.tool-versions".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn asdf_current_reads_installed_column() {
+        let stdout = "Name            Version         Source                     Installed\n\
+                      golang          1.22.1          /home/dev/.tool-versions   true\n\
+                      ruby            3.3.0           /home/dev/.tool-versions   false\n";
+
+        let tools = parse_current(stdout);
+
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].plugin, "golang");
+        assert_eq!(tools[0].source.as_deref(), Some("/home/dev/.tool-versions"));
+    }
+
+    #[test]
+    fn asdf_latest_feeds_status() {
+        let tools = parse_current(
+            "nodejs 20.11.0 /home/dev/.tool-versions\n\
+             golang 1.22.1 /home/dev/.tool-versions\n\
+             erlang 26.2 /home/dev/.tool-versions\n",
+        );
+        let latest = HashMap::from([
+            ("nodejs".to_string(), "20.12.2".to_string()),
+            ("golang".to_string(), "1.22.1".to_string()),
+        ]);
+
+        let records = build_records(tools, &latest);
+
+        assert_eq!(records[0].status, PackageStatus::Outdated);
+        assert_eq!(records[0].latest_version.as_deref(), Some("20.12.2"));
+        assert_eq!(records[1].status, PackageStatus::Current);
+        assert_eq!(records[2].status, PackageStatus::Unknown);
+    }
+}
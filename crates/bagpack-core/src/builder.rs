@@ -0,0 +1,131 @@
+use crate::{PackageManager, PackageRecord, PackageStatus, UNKNOWN_VERSION};
+
+impl PackageRecord {
+    /// Starts a record for `name` from `manager` with an [`UNKNOWN_VERSION`], no latest version
+    /// or install date, [`PackageStatus::Unknown`], and every flag unset.
+    pub fn builder(name: impl Into<String>, manager: PackageManager) -> PackageRecordBuilder {
+        PackageRecordBuilder {
+            record: PackageRecord {
+                name: name.into(),
+                current_version: UNKNOWN_VERSION.to_string(),
+                latest_version: None,
+                installed_at: None,
+                status: PackageStatus::Unknown,
+                manager,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            },
+        }
+    }
+}
+
+/// Builder returned by [`PackageRecord::builder`].
+#[derive(Debug, Clone)]
+pub struct PackageRecordBuilder {
+    record: PackageRecord,
+}
+
+impl PackageRecordBuilder {
+    pub fn current_version(mut self, version: impl Into<String>) -> Self {
+        self.record.current_version = version.into();
+        self
+    }
+
+    pub fn latest_version(mut self, version: impl Into<String>) -> Self {
+        self.record.latest_version = Some(version.into());
+        self
+    }
+
+    /// Sets the ISO 8601 install timestamp.
+    pub fn installed_at(mut self, timestamp: impl Into<String>) -> Self {
+        self.record.installed_at = Some(timestamp.into());
+        self
+    }
+
+    pub fn status(mut self, status: PackageStatus) -> Self {
+        self.record.status = status;
+        self
+    }
+
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.record.pinned = pinned;
+        self
+    }
+
+    pub fn dependency(mut self, dependency: bool) -> Self {
+        self.record.dependency = dependency;
+        self
+    }
+
+    pub fn linked(mut self, linked: bool) -> Self {
+        self.record.linked = linked;
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.record.source = Some(source.into());
+        self
+    }
+
+    pub fn homepage(mut self, homepage: impl Into<String>) -> Self {
+        self.record.homepage = Some(homepage.into());
+        self
+    }
+
+    pub fn deprecated(mut self, message: impl Into<String>) -> Self {
+        self.record.deprecated = Some(message.into());
+        self
+    }
+
+    pub fn provenance(mut self, commands: impl Into<String>) -> Self {
+        self.record.provenance = Some(commands.into());
+        self
+    }
+
+    pub fn download_size(mut self, bytes: u64) -> Self {
+        self.record.download_size = Some(bytes);
+        self
+    }
+
+    pub fn build(self) -> PackageRecord {
+        self.record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PackageManager, PackageRecord, PackageStatus, UNKNOWN_VERSION};
+
+    #[test]
+    fn builder_defaults_to_unknown_and_sets_fields() {
+        let bare = PackageRecord::builder("jq", PackageManager::Brew).build();
+        assert_eq!(bare.current_version, UNKNOWN_VERSION);
+        assert_eq!(bare.status, PackageStatus::Unknown);
+        assert_eq!(bare.latest_version, None);
+        assert!(!bare.pinned && !bare.dependency && !bare.linked);
+
+        let record = PackageRecord::builder("git", PackageManager::Choco)
+            .current_version("2.44.0")
+            .latest_version("2.45.1")
+            .installed_at("2024-09-17T08:22:00Z")
+            .status(PackageStatus::Outdated)
+            .pinned(true)
+            .source("chocolatey")
+            .build();
+
+        assert_eq!(record.name, "git");
+        assert_eq!(record.manager, PackageManager::Choco);
+        assert_eq!(record.current_version, "2.44.0");
+        assert_eq!(record.latest_version.as_deref(), Some("2.45.1"));
+        assert_eq!(record.installed_at.as_deref(), Some("2024-09-17T08:22:00Z"));
+        assert_eq!(record.status, PackageStatus::Outdated);
+        assert!(record.pinned);
+        assert_eq!(record.source.as_deref(), Some("chocolatey"));
+    }
+}
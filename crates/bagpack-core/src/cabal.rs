@@ -0,0 +1,90 @@
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_cabal(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "cabal",
+        &["list", "--installed", "--simple-output"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "cabal list --installed --simple-output")?;
+
+    // Checking Hackage for newer releases is left out, so status stays unknown.
+    let packages = parse_simple_output(&list_output.stdout)
+        .into_iter()
+        .map(|(name, current_version)| PackageRecord {
+            name,
+            current_version,
+            latest_version: None,
+            installed_at: None,
+            status: PackageStatus::Unknown,
+            manager: PackageManager::Cabal,
+            pinned: false,
+            dependency: false,
+            linked: false,
+            source: None,
+            homepage: None,
+            deprecated: None,
+            provenance: None,
+            download_size: None,
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses `--simple-output` rows of `name version`, one per installed version.
+///
+/// Status lines such as `Warning: ...` or `Resolving dependencies...` do not have exactly two
+/// columns and are skipped.
+fn parse_simple_output(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            match (columns.next(), columns.next(), columns.next()) {
+                (Some(name), Some(version), None)
+                    if version.starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    Some((name.to_string(), version.to_string()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_simple_output;
+
+    #[test]
+    fn cabal_simple_output_lists_each_installed_version() {
+        let stdout = "Warning: The package list for 'hackage.haskell.org' is 31 days old.\n\
+                      base 4.18.1.0\n\
+                      pandoc 3.1.11\n\
+                      text 2.0.2\n\
+                      text 2.1\n";
+
+        assert_eq!(
+            parse_simple_output(stdout),
+            vec![
+                ("base".to_string(), "4.18.1.0".to_string()),
+                ("pandoc".to_string(), "3.1.11".to_string()),
+                ("text".to_string(), "2.0.2".to_string()),
+                ("text".to_string(), "2.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn cabal_simple_output_without_packages_is_empty() {
+        assert!(parse_simple_output("").is_empty());
+        assert!(parse_simple_output("No matches found.\n").is_empty());
+    }
+}
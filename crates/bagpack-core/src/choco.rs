@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_choco(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "choco",
+        &["list", "--local-only", "--limit-output"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "choco list --local-only --limit-output")?;
+
+    let installed = parse_list(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(
+        options,
+        "choco",
+        &["outdated", "--limit-output"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&outdated_output, "choco outdated --limit-output")?;
+
+    let outdated = parse_outdated(&outdated_output.stdout);
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let update = outdated.get(&name);
+            let status = if update.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version: update.map(|update| update.available.clone()),
+                installed_at: None,
+                status,
+                manager: PackageManager::Choco,
+                pinned: update.is_some_and(|update| update.pinned),
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ChocoUpdate {
+    available: String,
+    pinned: bool,
+}
+
+/// Parses `name|version` rows from `choco list --limit-output`.
+fn parse_list(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, version) = line.trim().split_once('|')?;
+            (!name.is_empty()).then(|| (name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Parses `name|current|available|pinned` rows from `choco outdated --limit-output`.
+fn parse_outdated(stdout: &str) -> HashMap<String, ChocoUpdate> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.trim().split('|');
+            let name = columns.next().filter(|name| !name.is_empty())?;
+            let _current = columns.next()?;
+            let available = columns.next()?;
+            let pinned = columns
+                .next()
+                .is_some_and(|pinned| pinned.eq_ignore_ascii_case("true"));
+
+            Some((
+                name.to_string(),
+                ChocoUpdate {
+                    available: available.to_string(),
+                    pinned,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_list, parse_outdated, ChocoUpdate};
+
+    #[test]
+    fn choco_list_parses_pipe_separated_rows() {
+        let stdout = "chocolatey|2.2.2\r\ngit|2.44.0\r\nnodejs-lts|20.11.1\r\n";
+
+        assert_eq!(
+            parse_list(stdout),
+            vec![
+                ("chocolatey".to_string(), "2.2.2".to_string()),
+                ("git".to_string(), "2.44.0".to_string()),
+                ("nodejs-lts".to_string(), "20.11.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn choco_outdated_reads_pinned_flag() {
+        let stdout = "git|2.44.0|2.45.1|false\r\nnodejs-lts|20.11.1|20.12.2|true\r\n";
+
+        let outdated = parse_outdated(stdout);
+
+        assert_eq!(
+            outdated["git"],
+            ChocoUpdate {
+                available: "2.45.1".into(),
+                pinned: false,
+            }
+        );
+        assert!(outdated["nodejs-lts"].pinned);
+    }
+}
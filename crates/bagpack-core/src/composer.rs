@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_composer(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let show_output = run_command(
+        options,
+        "composer",
+        &["global", "show", "--format=json"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&show_output, "composer global show --format=json")?;
+
+    let installed = parse_packages(&show_output.stdout)?;
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(
+        options,
+        "composer",
+        &["global", "outdated", "--format=json"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&outdated_output, "composer global outdated --format=json")?;
+
+    let latest_map: HashMap<String, String> = parse_packages(&outdated_output.stdout)?
+        .into_iter()
+        .filter_map(|pkg| pkg.latest.map(|latest| (pkg.name, latest)))
+        .collect();
+
+    Ok(build_records(installed, &latest_map))
+}
+
+#[derive(Debug, Deserialize)]
+struct ComposerListing {
+    #[serde(default)]
+    installed: Vec<ComposerPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComposerPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    latest: Option<String>,
+}
+
+/// Parses the `{"installed": [...]}` document shared by `show` and `outdated`.
+fn parse_packages(stdout: &str) -> Result<Vec<ComposerPackage>, CollectionError> {
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let listing: ComposerListing = serde_json::from_str(stdout)?;
+    Ok(listing.installed)
+}
+
+fn build_records(
+    installed: Vec<ComposerPackage>,
+    latest_map: &HashMap<String, String>,
+) -> Vec<PackageRecord> {
+    installed
+        .into_iter()
+        .map(|pkg| {
+            let latest_version = latest_map
+                .get(&pkg.name)
+                .filter(|latest| **latest != pkg.version)
+                .cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name: pkg.name,
+                current_version: pkg.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Composer,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_packages};
+    use crate::PackageStatus;
+    use std::collections::HashMap;
+
+    #[test]
+    fn composer_show_reads_installed_key() {
+        let stdout = r#"{
+            "installed": [
+                {"name": "laravel/installer", "version": "v5.2.0", "description": "Laravel application installer."},
+                {"name": "friendsofphp/php-cs-fixer", "version": "v3.64.0", "description": "A tool to automatically fix PHP code style"}
+            ]
+        }"#;
+
+        let packages = parse_packages(stdout).unwrap();
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "laravel/installer");
+        assert_eq!(packages[0].version, "v5.2.0");
+    }
+
+    #[test]
+    fn composer_outdated_supplies_latest_versions() {
+        let installed = parse_packages(
+            r#"{"installed": [{"name": "laravel/installer", "version": "v5.2.0"}, {"name": "phpstan/phpstan", "version": "1.12.4"}]}"#,
+        )
+        .unwrap();
+        let outdated = parse_packages(
+            r#"{"installed": [{"name": "laravel/installer", "version": "v5.2.0", "latest": "v5.8.3", "latest-status": "semver-safe-update"}]}"#,
+        )
+        .unwrap();
+        let latest_map: HashMap<String, String> = outdated
+            .into_iter()
+            .filter_map(|pkg| pkg.latest.map(|latest| (pkg.name, latest)))
+            .collect();
+
+        let records = build_records(installed, &latest_map);
+
+        assert_eq!(records[0].status, PackageStatus::Outdated);
+        assert_eq!(records[0].latest_version.as_deref(), Some("v5.8.3"));
+        assert_eq!(records[1].status, PackageStatus::Current);
+    }
+
+    #[test]
+    fn composer_empty_output_has_no_packages() {
+        assert!(parse_packages("").unwrap().is_empty());
+        assert!(parse_packages("{}").unwrap().is_empty());
+    }
+}
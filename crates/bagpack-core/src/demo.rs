@@ -0,0 +1,27 @@
+use crate::{InventorySnapshot, PackageManager, PackageRecord, PackageStatus};
+
+/// Sample snapshot matching the payload documented in the README, for UI previews and tests.
+pub fn demo_snapshot() -> InventorySnapshot {
+    InventorySnapshot {
+        generated_at: Some("2025-10-05T12:34:56Z".into()),
+        packages: vec![
+            PackageRecord::builder("wget", PackageManager::Brew)
+                .current_version("1.24.5")
+                .latest_version("1.24.6")
+                .installed_at("2024-09-17T08:22:00Z")
+                .status(PackageStatus::Outdated)
+                .build(),
+            PackageRecord::builder("typescript", PackageManager::Npm)
+                .current_version("5.5.2")
+                .latest_version("5.6.3")
+                .installed_at("2025-02-11T15:10:30Z")
+                .status(PackageStatus::Current)
+                .build(),
+            PackageRecord::builder("requests", PackageManager::Pip)
+                .current_version("2.32.3")
+                .latest_version("2.33.0")
+                .build(),
+        ],
+        total: None,
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{run_bounded, CollectInventoryOptions, PackageManager};
+
+/// How long a manager's `--version` may take before it is considered unavailable.
+const DETECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a detection result is reused before the managers are probed again.
+const DETECT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Interpreter locations checked after pyenv and `PATH`.
+const COMMON_PYTHON_PATHS: &[&str] = &[
+    "/usr/bin/python3",
+    "/usr/local/bin/python3",
+    "/opt/homebrew/bin/python3",
+    "/opt/local/bin/python3",
+];
+
+/// Recent detection results, keyed by the extra environment they were probed with.
+static DETECT_CACHE: Mutex<Vec<CachedDetection>> = Mutex::new(Vec::new());
+
+struct CachedDetection {
+    env: HashMap<String, String>,
+    detected_at: Instant,
+    managers: Vec<PackageManager>,
+}
+
+/// Lists the managers whose CLI is installed, in [`PackageManager::ALL`] order.
+///
+/// Each binary is probed with `--version`; it counts as available when it exits successfully
+/// within a short timeout. Managers compiled out of this build are never reported.
+///
+/// Results are cached in memory for a minute so reopening a UI does not re-probe every
+/// binary; `force` probes again regardless.
+pub fn detect_available_managers(force: bool) -> Vec<PackageManager> {
+    detect_available_managers_with(&CollectInventoryOptions::default(), force)
+}
+
+/// Like [`detect_available_managers`], but resolves binaries with `options.env` applied and
+/// probes at most `options.max_concurrency` managers at once.
+///
+/// The cache is kept per `options.env`, since that can change which binaries are found.
+pub fn detect_available_managers_with(
+    options: &CollectInventoryOptions,
+    force: bool,
+) -> Vec<PackageManager> {
+    let mut cache = DETECT_CACHE.lock().unwrap();
+    cache.retain(|cached| cached.detected_at.elapsed() < DETECT_CACHE_TTL);
+    if !force {
+        if let Some(cached) = cache.iter().find(|cached| cached.env == options.env) {
+            return cached.managers.clone();
+        }
+    }
+    // Probe without holding the lock so callers with other environments are not blocked.
+    drop(cache);
+
+    let managers = probe_managers(options);
+
+    let mut cache = DETECT_CACHE.lock().unwrap();
+    cache.retain(|cached| cached.env != options.env);
+    cache.push(CachedDetection {
+        env: options.env.clone(),
+        detected_at: Instant::now(),
+        managers: managers.clone(),
+    });
+
+    managers
+}
+
+fn probe_managers(options: &CollectInventoryOptions) -> Vec<PackageManager> {
+    let candidates: Vec<PackageManager> = PackageManager::ALL
+        .iter()
+        .filter(|manager| cfg!(feature = "zypper") || **manager != PackageManager::Zypper)
+        .cloned()
+        .collect();
+
+    let jobs = candidates
+        .iter()
+        .map(|manager| move || responds_to_version(options, manager.program()))
+        .collect();
+    let available = run_bounded(options.max_concurrency, jobs);
+
+    candidates
+        .into_iter()
+        .zip(available)
+        .filter_map(|(manager, available)| available.then_some(manager))
+        .collect()
+}
+
+/// Lists the Python interpreters on this machine, for picking which `pip` to collect from.
+///
+/// Candidates come from pyenv's installed versions, every `python3` and `python` on `PATH` (like
+/// `which -a`), and a few common install locations, in that order. Each one must answer
+/// `--version` to be reported, and interpreters reached through several paths are reported once.
+pub fn detect_python_environments() -> Vec<PathBuf> {
+    detect_python_environments_with(&CollectInventoryOptions::default())
+}
+
+/// Like [`detect_python_environments`], but resolves `pyenv` and `PATH` with `options.env`
+/// applied.
+pub fn detect_python_environments_with(options: &CollectInventoryOptions) -> Vec<PathBuf> {
+    let mut candidates = pyenv_pythons(options);
+
+    let path = options
+        .env
+        .get("PATH")
+        .map(Into::into)
+        .or_else(|| std::env::var_os("PATH"))
+        .unwrap_or_default();
+    for dir in std::env::split_paths(&path) {
+        candidates.extend(["python3", "python"].map(|name| dir.join(name)));
+    }
+    candidates.extend(COMMON_PYTHON_PATHS.iter().map(PathBuf::from));
+
+    let mut seen = Vec::new();
+    candidates.retain(|candidate| {
+        let Ok(real) = candidate.canonicalize() else {
+            return false;
+        };
+        if seen.contains(&real) {
+            return false;
+        }
+        seen.push(real);
+        true
+    });
+
+    let jobs = candidates
+        .iter()
+        .map(|candidate| move || responds_to_version(options, candidate))
+        .collect();
+    let valid = run_bounded(options.max_concurrency, jobs);
+
+    candidates
+        .into_iter()
+        .zip(valid)
+        .filter_map(|(candidate, valid)| valid.then_some(candidate))
+        .collect()
+}
+
+/// Interpreters under `$(pyenv root)/versions`, sorted by version directory name.
+///
+/// `pyenv root` gets the same [`DETECT_TIMEOUT`] as the other probes, so a hung shim cannot
+/// stall detection.
+fn pyenv_pythons(options: &CollectInventoryOptions) -> Vec<PathBuf> {
+    let Ok(mut child) = Command::new("pyenv")
+        .arg("root")
+        .envs(&options.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return Vec::new();
+    };
+    let mut stdout = child.stdout.take();
+    let reader = thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_end(&mut bytes);
+        }
+        bytes
+    });
+    // On timeout the reader is left to finish on its own, in case a killed shim's children
+    // still hold the pipe open.
+    if !wait_with_deadline(&mut child) {
+        return Vec::new();
+    }
+    let root = String::from_utf8_lossy(&reader.join().unwrap_or_default())
+        .trim()
+        .to_string();
+    let Ok(versions) = std::fs::read_dir(Path::new(&root).join("versions")) else {
+        return Vec::new();
+    };
+
+    let mut pythons: Vec<PathBuf> = versions
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().join("bin").join("python3"))
+        .filter(|python| python.is_file())
+        .collect();
+    pythons.sort();
+    pythons
+}
+
+fn responds_to_version(options: &CollectInventoryOptions, program: impl AsRef<OsStr>) -> bool {
+    let Ok(mut child) = Command::new(program)
+        .arg("--version")
+        .envs(&options.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+
+    wait_with_deadline(&mut child)
+}
+
+/// Waits up to [`DETECT_TIMEOUT`] for `child` and returns whether it exited successfully,
+/// killing it once the deadline passes.
+fn wait_with_deadline(child: &mut Child) -> bool {
+    let deadline = Instant::now() + DETECT_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
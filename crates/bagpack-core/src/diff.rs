@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{compare_versions, InventorySnapshot, PackageManager, PackageRecord};
+
+/// Differences between two snapshots, keyed by `(manager, name)`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Records present only in the newer snapshot.
+    pub added: Vec<PackageRecord>,
+    /// Records present only in the older snapshot.
+    pub removed: Vec<PackageRecord>,
+    /// Records present in both whose `current_version` differs.
+    pub changed: Vec<VersionChange>,
+}
+
+/// A package whose installed version changed between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionChange {
+    pub manager: PackageManager,
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl SnapshotDiff {
+    /// Returns whether the two snapshots had the same packages and versions.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares `old` against `new`.
+///
+/// Added and changed entries follow `new`'s order; removed entries follow `old`'s order.
+pub fn diff_snapshots(old: &InventorySnapshot, new: &InventorySnapshot) -> SnapshotDiff {
+    fn index(snapshot: &InventorySnapshot) -> HashMap<(&PackageManager, &str), usize> {
+        snapshot
+            .packages
+            .iter()
+            .enumerate()
+            .map(|(position, record)| ((&record.manager, record.name.as_str()), position))
+            .collect()
+    }
+    let old_index = index(old);
+    let new_index = index(new);
+
+    let mut diff = SnapshotDiff::default();
+
+    for record in &new.packages {
+        match old_index.get(&(&record.manager, record.name.as_str())) {
+            Some(&position) => {
+                let previous = &old.packages[position];
+                if !previous.same_version(record) {
+                    diff.changed.push(VersionChange {
+                        manager: record.manager.clone(),
+                        name: record.name.clone(),
+                        from: previous.current_version.clone(),
+                        to: record.current_version.clone(),
+                    });
+                }
+            }
+            None => diff.added.push(record.clone()),
+        }
+    }
+
+    diff.removed = old
+        .packages
+        .iter()
+        .filter(|record| !new_index.contains_key(&(&record.manager, record.name.as_str())))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// Renders `diff` as a Markdown changelog with "Added", "Removed", "Upgraded", and
+/// "Downgraded" sections; empty sections are omitted.
+pub fn diff_to_markdown(diff: &SnapshotDiff) -> String {
+    let record_line = |record: &PackageRecord| {
+        format!(
+            "- {} {} ({})",
+            record.name,
+            record.current_version,
+            record.manager.as_str()
+        )
+    };
+    let change_line = |change: &&VersionChange| {
+        format!(
+            "- {} {} → {} ({})",
+            change.name,
+            change.from,
+            change.to,
+            change.manager.as_str()
+        )
+    };
+    let (downgraded, upgraded): (Vec<&VersionChange>, Vec<&VersionChange>) = diff
+        .changed
+        .iter()
+        .partition(|change| compare_versions(&change.to, &change.from) == Ordering::Less);
+
+    let sections = [
+        (
+            "Added",
+            diff.added.iter().map(record_line).collect::<Vec<_>>(),
+        ),
+        ("Removed", diff.removed.iter().map(record_line).collect()),
+        ("Upgraded", upgraded.iter().map(change_line).collect()),
+        ("Downgraded", downgraded.iter().map(change_line).collect()),
+    ];
+
+    let mut out = String::new();
+    for (title, lines) in sections.iter().filter(|(_, lines)| !lines.is_empty()) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## {title}\n\n"));
+        for line in lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_snapshots, diff_to_markdown, SnapshotDiff, VersionChange};
+    use crate::{demo_snapshot, PackageManager, PackageRecord};
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let old = demo_snapshot();
+        let mut new = demo_snapshot();
+        new.packages.retain(|record| record.name != "requests");
+        new.packages[0].current_version = "1.24.6".into();
+        new.push(PackageRecord {
+            name: "requests".into(),
+            manager: PackageManager::Brew,
+            ..old.packages[2].clone()
+        });
+
+        let diff = diff_snapshots(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].manager, PackageManager::Brew);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].manager, PackageManager::Pip);
+        assert_eq!(
+            diff.changed,
+            vec![VersionChange {
+                manager: PackageManager::Brew,
+                name: "wget".into(),
+                from: "1.24.5".into(),
+                to: "1.24.6".into(),
+            }]
+        );
+        assert!(diff_snapshots(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn regressions_report_only_downgrades() {
+        let baseline = demo_snapshot();
+        let mut current = demo_snapshot();
+        current.packages[0].current_version = "1.24.6".into();
+        current.packages[1].current_version = "5.4.5".into();
+
+        assert_eq!(
+            current.regressions(&baseline),
+            vec![VersionChange {
+                manager: PackageManager::Npm,
+                name: "typescript".into(),
+                from: "5.5.2".into(),
+                to: "5.4.5".into(),
+            }]
+        );
+        assert!(baseline.regressions(&baseline).is_empty());
+    }
+
+    #[test]
+    fn regressions_match_packages_by_manager() {
+        let baseline = demo_snapshot();
+        let mut current = demo_snapshot();
+        // An older `requests` under a different manager is a new package, not a downgrade.
+        current.push(PackageRecord {
+            manager: PackageManager::Brew,
+            current_version: "2.0.0".into(),
+            ..baseline.packages[2].clone()
+        });
+
+        assert!(current.regressions(&baseline).is_empty());
+    }
+
+    #[test]
+    fn markdown_changelog_has_a_section_per_category() {
+        let snapshot = demo_snapshot();
+        let change = |name: &str, from: &str, to: &str| VersionChange {
+            manager: PackageManager::Brew,
+            name: name.into(),
+            from: from.into(),
+            to: to.into(),
+        };
+        let diff = SnapshotDiff {
+            added: vec![snapshot.packages[1].clone()],
+            removed: vec![snapshot.packages[2].clone()],
+            changed: vec![
+                change("wget", "1.24.5", "1.24.6"),
+                change("jq", "1.7.1", "1.6"),
+            ],
+        };
+
+        assert_eq!(
+            diff_to_markdown(&diff),
+            "## Added\n\n- typescript 5.5.2 (npm)\n\n\
+             ## Removed\n\n- requests 2.32.3 (pip)\n\n\
+             ## Upgraded\n\n- wget 1.24.5 → 1.24.6 (brew)\n\n\
+             ## Downgraded\n\n- jq 1.7.1 → 1.6 (brew)\n"
+        );
+    }
+
+    #[test]
+    fn markdown_changelog_omits_empty_sections() {
+        let diff = SnapshotDiff {
+            changed: vec![VersionChange {
+                manager: PackageManager::Npm,
+                name: "typescript".into(),
+                from: "5.5.2".into(),
+                to: "5.6.3".into(),
+            }],
+            ..SnapshotDiff::default()
+        };
+
+        assert_eq!(
+            diff_to_markdown(&diff),
+            "## Upgraded\n\n- typescript 5.5.2 → 5.6.3 (npm)\n"
+        );
+        assert_eq!(diff_to_markdown(&SnapshotDiff::default()), "");
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_dnf(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "dnf", &["list", "installed"], None::<&[i32]>)?;
+    ensure_success(&list_output, "dnf list installed")?;
+
+    let installed = parse_rows(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // `check-update` exits with 100 when updates are available and 0 when there are none.
+    let check_args: &[&str] = if options.security_only {
+        &["check-update", "--security"]
+    } else {
+        &["check-update"]
+    };
+    let check_output = run_command(options, "dnf", check_args, Some(&[0, 100]))?;
+    ensure_success(&check_output, &format!("dnf {}", check_args.join(" ")))?;
+
+    let updates: HashMap<String, String> = parse_rows(&check_output.stdout).into_iter().collect();
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = updates.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Dnf,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses `name.arch version repo` rows shared by `dnf list installed` and `dnf check-update`,
+/// returning `(name, version)` with the architecture suffix removed.
+///
+/// dnf puts names too long for the first column on a line of their own and the remaining
+/// columns on the next line, so a lone column is carried over. Section headers
+/// (`Installed Packages`), metadata notes, and the `Obsoleting Packages` section of
+/// `check-update` are skipped.
+fn parse_rows(stdout: &str) -> Vec<(String, String)> {
+    let mut rows = Vec::new();
+    let mut wrapped_name: Option<&str> = None;
+
+    for line in stdout.lines() {
+        if line.starts_with("Obsoleting Packages") {
+            break;
+        }
+
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let (package, version) = match (wrapped_name.take(), columns.as_slice()) {
+            (None, [package]) if package.contains('.') => {
+                wrapped_name = Some(package);
+                continue;
+            }
+            (Some(package), [version, _repo]) => (package, *version),
+            (None, [package, version, _repo]) => (*package, *version),
+            _ => continue,
+        };
+
+        let Some((name, _arch)) = package.rsplit_once('.') else {
+            continue;
+        };
+        if !version.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        rows.push((name.to_string(), version.to_string()));
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_rows;
+
+    #[test]
+    fn dnf_list_installed_strips_arch_and_joins_wrapped_rows() {
+        let stdout = "Installed Packages\n\
+                      bash.x86_64                          5.2.26-3.fc40                 @fedora\n\
+                      NetworkManager-libnm.x86_64          1:1.46.0-1.fc40               @updates\n\
+                      google-noto-sans-cjk-vf-fonts.noarch\n\
+                      \x20                                    1:2.004-7.fc40                @fedora\n";
+
+        assert_eq!(
+            parse_rows(stdout),
+            vec![
+                ("bash".to_string(), "5.2.26-3.fc40".to_string()),
+                (
+                    "NetworkManager-libnm".to_string(),
+                    "1:1.46.0-1.fc40".to_string()
+                ),
+                (
+                    "google-noto-sans-cjk-vf-fonts".to_string(),
+                    "1:2.004-7.fc40".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn dnf_check_update_skips_notes_and_obsoletes() {
+        let stdout = "Last metadata expiration check: 0:12:03 ago on Mon 15 Apr 2024 09:00:00 AM UTC.\n\
+                      \n\
+                      bash.x86_64                          5.2.26-4.fc40                 updates\n\
+                      \n\
+                      Obsoleting Packages\n\
+                      grub2-tools.x86_64                   1:2.06-120.fc40               updates\n\
+                      \x20   grub2-tools.x86_64               1:2.06-118.fc40               @updates\n";
+
+        assert_eq!(
+            parse_rows(stdout),
+            vec![("bash".to_string(), "5.2.26-4.fc40".to_string())]
+        );
+    }
+
+    #[test]
+    fn dnf_check_update_security_skips_advisory_summary() {
+        let stdout =
+            "Last metadata expiration check: 0:41:10 ago on Tue 16 Apr 2024 10:00:00 AM UTC.\n\
+                      2 package(s) needed for security, out of 14 available\n\
+                      \n\
+                      openssl-libs.x86_64                  1:3.2.1-2.fc40                updates\n\
+                      curl.x86_64                          8.6.0-8.fc40                  updates\n";
+
+        assert_eq!(
+            parse_rows(stdout),
+            vec![
+                ("openssl-libs".to_string(), "1:3.2.1-2.fc40".to_string()),
+                ("curl".to_string(), "8.6.0-8.fc40".to_string()),
+            ]
+        );
+        assert!(parse_rows("No security updates needed, but 14 updates available\n").is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_dotnet_tool(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "dotnet",
+        &["tool", "list", "--global"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "dotnet tool list --global")?;
+
+    // There is no bulk outdated query short of asking NuGet per package, so status stays
+    // unknown.
+    let packages = parse_table(&list_output.stdout)
+        .into_iter()
+        .map(|(name, current_version)| PackageRecord {
+            name,
+            current_version,
+            latest_version: None,
+            installed_at: None,
+            status: PackageStatus::Unknown,
+            manager: PackageManager::DotnetTool,
+            pinned: false,
+            dependency: false,
+            linked: false,
+            source: None,
+            homepage: None,
+            deprecated: None,
+            provenance: None,
+            download_size: None,
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses the fixed-width table printed by `dotnet tool list --global`:
+///
+/// ```text
+/// Package Id      Version      Commands
+/// -------------------------------------------
+/// dotnetsay       2.1.4        dotnetsay
+/// ```
+///
+/// Column boundaries come from the header, and the dashed separator line is skipped.
+fn parse_table(stdout: &str) -> Vec<(String, String)> {
+    let mut lines = stdout.lines();
+    let Some(header) = lines.find(|line| line.trim_start().starts_with("Package Id")) else {
+        return Vec::new();
+    };
+    let Some(version_start) = header.find("Version") else {
+        return Vec::new();
+    };
+    let version_end = header.find("Commands").unwrap_or(usize::MAX);
+
+    lines
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('-'))
+        .filter_map(|line| {
+            let name = column(line, 0, version_start)?;
+            let version = column(line, version_start, version_end)?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+fn column(line: &str, start: usize, end: usize) -> Option<&str> {
+    let end = end.min(line.len());
+    let value = line.get(start..end)?.trim();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_table;
+
+    #[test]
+    fn dotnet_tool_table_uses_header_columns() {
+        let stdout = "Package Id                 Version      Commands\n\
+                      -----------------------------------------------------\n\
+                      dotnet-ef                  8.0.1        dotnet-ef\n\
+                      microsoft.dotnet-httprepl  7.0.0-rc.1   httprepl\n\
+                      dotnetsay                  2.1.4        dotnetsay\n";
+
+        assert_eq!(
+            parse_table(stdout),
+            vec![
+                ("dotnet-ef".to_string(), "8.0.1".to_string()),
+                (
+                    "microsoft.dotnet-httprepl".to_string(),
+                    "7.0.0-rc.1".to_string()
+                ),
+                ("dotnetsay".to_string(), "2.1.4".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn dotnet_tool_table_without_rows_is_empty() {
+        let stdout = "Package Id      Version      Commands\n\
+                      -------------------------------------\n";
+
+        assert!(parse_table(stdout).is_empty());
+        assert!(parse_table("").is_empty());
+    }
+}
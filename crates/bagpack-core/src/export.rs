@@ -0,0 +1,781 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::{InventorySnapshot, PackageManager, PackageRecord, REDACTED_NAME, UNKNOWN_VERSION};
+
+/// A [`PackageRecord`] column that the tabular exporters can emit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportField {
+    Manager,
+    Name,
+    CurrentVersion,
+    LatestVersion,
+    InstalledAt,
+    Status,
+    Source,
+    Pinned,
+    Dependency,
+    Linked,
+    Homepage,
+    Deprecated,
+    Provenance,
+}
+
+impl ExportField {
+    /// The columns exported when no selection is made, in their historical order.
+    pub const DEFAULT: &'static [ExportField] = &[
+        ExportField::Manager,
+        ExportField::Name,
+        ExportField::CurrentVersion,
+        ExportField::LatestVersion,
+        ExportField::InstalledAt,
+        ExportField::Status,
+        ExportField::Source,
+    ];
+
+    /// Returns the column header, which matches the JSON field name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportField::Manager => "manager",
+            ExportField::Name => "name",
+            ExportField::CurrentVersion => "current_version",
+            ExportField::LatestVersion => "latest_version",
+            ExportField::InstalledAt => "installed_at",
+            ExportField::Status => "status",
+            ExportField::Source => "source",
+            ExportField::Pinned => "pinned",
+            ExportField::Dependency => "dependency",
+            ExportField::Linked => "linked",
+            ExportField::Homepage => "homepage",
+            ExportField::Deprecated => "deprecated",
+            ExportField::Provenance => "provenance",
+        }
+    }
+
+    /// Returns the record's value for this column; missing optional values are empty.
+    fn value<'a>(&self, record: &'a PackageRecord) -> &'a str {
+        let flag = |set: bool| if set { "true" } else { "false" };
+        match self {
+            ExportField::Manager => record.manager.as_str(),
+            ExportField::Name => &record.name,
+            ExportField::CurrentVersion => &record.current_version,
+            ExportField::LatestVersion => record.latest_version.as_deref().unwrap_or_default(),
+            ExportField::InstalledAt => record.installed_at.as_deref().unwrap_or_default(),
+            ExportField::Status => record.status.as_str(),
+            ExportField::Source => record.source.as_deref().unwrap_or_default(),
+            ExportField::Pinned => flag(record.pinned),
+            ExportField::Dependency => flag(record.dependency),
+            ExportField::Linked => flag(record.linked),
+            ExportField::Homepage => record.homepage.as_deref().unwrap_or_default(),
+            ExportField::Deprecated => record.deprecated.as_deref().unwrap_or_default(),
+            ExportField::Provenance => record.provenance.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+/// Display settings for the tabular exporters; the snapshot itself is never modified.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Show `installed_at` shifted to this UTC offset, e.g. the viewer's local time. Values that
+    /// are not RFC 3339 timestamps are shown verbatim.
+    pub utc_offset: Option<UtcOffset>,
+}
+
+impl ExportOptions {
+    fn cell<'a>(&self, field: ExportField, record: &'a PackageRecord) -> Cow<'a, str> {
+        let value = field.value(record);
+        match (field, self.utc_offset) {
+            (ExportField::InstalledAt, Some(offset)) => OffsetDateTime::parse(value, &Rfc3339)
+                .ok()
+                .and_then(|timestamp| timestamp.to_offset(offset).format(&Rfc3339).ok())
+                .map_or(Cow::Borrowed(value), Cow::Owned),
+            _ => Cow::Borrowed(value),
+        }
+    }
+}
+
+/// Renders the snapshot as CSV with a header row, emitting `fields` in the given order.
+///
+/// Pass [`ExportField::DEFAULT`] for the standard columns.
+pub fn to_csv(snapshot: &InventorySnapshot, fields: &[ExportField]) -> String {
+    to_csv_with(snapshot, fields, &ExportOptions::default())
+}
+
+/// Like [`to_csv`], but formats cells according to `options`.
+pub fn to_csv_with(
+    snapshot: &InventorySnapshot,
+    fields: &[ExportField],
+    options: &ExportOptions,
+) -> String {
+    let header: Vec<&str> = fields.iter().map(ExportField::as_str).collect();
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for record in &snapshot.packages {
+        let cells: Vec<String> = fields
+            .iter()
+            .map(|field| csv_cell(&options.cell(*field, record)))
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Renders the snapshot as a Markdown table, emitting `fields` in the given order.
+pub fn to_markdown(snapshot: &InventorySnapshot, fields: &[ExportField]) -> String {
+    to_markdown_with(snapshot, fields, &ExportOptions::default())
+}
+
+/// Like [`to_markdown`], but formats cells according to `options`.
+pub fn to_markdown_with(
+    snapshot: &InventorySnapshot,
+    fields: &[ExportField],
+    options: &ExportOptions,
+) -> String {
+    let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+
+    let header = fields
+        .iter()
+        .map(|field| field.as_str().to_string())
+        .collect();
+    let divider = fields.iter().map(|_| "---".to_string()).collect();
+
+    let mut out = row(header);
+    out.push_str(&row(divider));
+    for record in &snapshot.packages {
+        let cells = fields
+            .iter()
+            .map(|field| markdown_cell(&options.cell(*field, record)))
+            .collect();
+        out.push_str(&row(cells));
+    }
+
+    out
+}
+
+/// Names longer than this are cut short in [`to_table`] so one package cannot widen every row.
+const TABLE_NAME_WIDTH: usize = 40;
+
+/// Renders the snapshot as a plain-text table with a header row, padding each column to its
+/// widest cell. Names over 40 characters end in an ellipsis.
+pub fn to_table(snapshot: &InventorySnapshot) -> String {
+    let header = ["MANAGER", "NAME", "CURRENT", "LATEST", "STATUS"].map(String::from);
+    let rows: Vec<[String; 5]> = snapshot
+        .packages
+        .iter()
+        .map(|record| {
+            [
+                record.manager.as_str().to_string(),
+                truncate_cell(&record.name, TABLE_NAME_WIDTH),
+                record.current_version.clone(),
+                record.latest_version.as_deref().unwrap_or("-").to_string(),
+                record.status.as_str().to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.each_ref().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders the snapshot as JSON, indented when `pretty` is set and on a single line otherwise
+/// (e.g. for IPC payloads).
+pub fn to_json(snapshot: &InventorySnapshot, pretty: bool) -> String {
+    let json = if pretty {
+        serde_json::to_string_pretty(snapshot)
+    } else {
+        serde_json::to_string(snapshot)
+    };
+    json.expect("snapshot serializes to JSON")
+}
+
+/// Writes the snapshot as pretty-printed JSON; see [`to_json`] for the compact form.
+pub fn write_json(snapshot: &InventorySnapshot, mut writer: impl io::Write) -> io::Result<()> {
+    writer.write_all(to_json(snapshot, true).as_bytes())
+}
+
+/// Document format for whole-snapshot exports such as [`write_split`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Pretty-printed JSON, as written by [`write_json`].
+    Json,
+    /// [`to_csv`] with the [`ExportField::DEFAULT`] columns.
+    Csv,
+    /// [`to_markdown`] with the [`ExportField::DEFAULT`] columns.
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Returns the file extension (without the dot) for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// Renders `snapshot` in this format.
+    pub fn render(&self, snapshot: &InventorySnapshot) -> String {
+        match self {
+            ExportFormat::Json => to_json(snapshot, true),
+            ExportFormat::Csv => to_csv(snapshot, ExportField::DEFAULT),
+            ExportFormat::Markdown => to_markdown(snapshot, ExportField::DEFAULT),
+        }
+    }
+}
+
+/// Writes one file per manager into `dir`, named after the manager (`brew.json`, `npm.json`,
+/// ...), each holding only that manager's records and the snapshot's `generated_at`.
+///
+/// Managers without packages get no file, and existing files with the same names are
+/// overwritten. `dir` must already exist.
+///
+/// Custom manager names come straight from the snapshot, so a name that is empty or contains a
+/// path separator or `..` fails with [`io::ErrorKind::InvalidInput`] before anything is written.
+pub fn write_split(
+    snapshot: &InventorySnapshot,
+    dir: &Path,
+    format: ExportFormat,
+) -> io::Result<()> {
+    let groups = snapshot.group_by_manager();
+    if let Some(manager) = groups.keys().find(|manager| {
+        let name = manager.as_str();
+        name.is_empty() || name.contains(['/', '\\']) || name.contains("..")
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "manager name is not a safe file name: {:?}",
+                manager.as_str()
+            ),
+        ));
+    }
+
+    for (manager, records) in groups {
+        let part = snapshot.with_packages(records.into_iter().cloned().collect());
+        let path = dir.join(format!("{}.{}", manager.as_str(), format.extension()));
+        fs::write(path, format.render(&part))?;
+    }
+    Ok(())
+}
+
+/// Renders a shell script that reinstalls the snapshot's packages, one section per manager.
+///
+/// Versions are pinned where the manager supports it and `current_version` is known.
+/// Dependencies, linked checkouts, and redacted names are left out, and records a manager
+/// cannot reinstall from the CLI (such as rustup components) are listed as comments.
+pub fn to_install_script(snapshot: &InventorySnapshot) -> String {
+    let mut out = String::from("#!/bin/sh\nset -e\n");
+
+    for (manager, records) in snapshot.group_by_manager() {
+        let records: Vec<&PackageRecord> = records
+            .into_iter()
+            .filter(|record| !record.dependency && !record.linked && record.name != REDACTED_NAME)
+            .collect();
+        if records.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("\n# {}\n", manager.as_str()));
+        for record in records {
+            match install_command(record) {
+                Some(argv) => {
+                    let words: Vec<String> = argv.iter().map(|word| shell_word(word)).collect();
+                    out.push_str(&words.join(" "));
+                }
+                None => out.push_str(&format!("# not reinstallable: {}", record.name)),
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Manager name that [`to_winget_import`] exports.
+///
+/// bagpack has no built-in winget collector, so these records come from a custom
+/// [`Collector`](crate::Collector) reporting [`PackageManager::Other`]`("winget")`.
+const WINGET: &str = "winget";
+
+/// Renders the snapshot's winget records as a `winget import` file.
+///
+/// Only records whose manager is named `winget` are exported, which today means records from
+/// a custom [`Collector`](crate::Collector) reporting [`PackageManager::Other`]`("winget")`;
+/// built-in scans never produce them.
+///
+/// Records are grouped by `source` into the `msstore` or (by default) `winget` source, with
+/// `Version` pinned when `current_version` is known. Redacted names are left out.
+pub fn to_winget_import(snapshot: &InventorySnapshot) -> String {
+    let source_details = |name: &str| match name {
+        "msstore" => serde_json::json!({
+            "Argument": "https://storeedgefd.dsx.mp.microsoft.com/v9.0",
+            "Identifier": "StoreEdgeFD",
+            "Name": "msstore",
+            "Type": "Microsoft.Rest",
+        }),
+        _ => serde_json::json!({
+            "Argument": "https://cdn.winget.microsoft.com/cache",
+            "Identifier": "Microsoft.Winget.Source_8wekyb3d8bbwe",
+            "Name": "winget",
+            "Type": "Microsoft.PreIndexed.Package",
+        }),
+    };
+
+    let mut sources: Vec<(&str, Vec<serde_json::Value>)> = Vec::new();
+    for record in snapshot
+        .packages
+        .iter()
+        .filter(|record| record.manager.as_str() == WINGET && record.name != REDACTED_NAME)
+    {
+        let source = match record.source.as_deref() {
+            Some("msstore") => "msstore",
+            _ => "winget",
+        };
+        let mut package = serde_json::json!({ "PackageIdentifier": record.name });
+        if record.current_version != UNKNOWN_VERSION {
+            package["Version"] = record.current_version.clone().into();
+        }
+        match sources.iter_mut().find(|(name, _)| *name == source) {
+            Some((_, packages)) => packages.push(package),
+            None => sources.push((source, vec![package])),
+        }
+    }
+
+    let mut import = serde_json::json!({
+        "$schema": "https://aka.ms/winget-packages.schema.2.0.json",
+        "Sources": sources
+            .into_iter()
+            .map(|(name, packages)| serde_json::json!({
+                "Packages": packages,
+                "SourceDetails": source_details(name),
+            }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(generated_at) = &snapshot.generated_at {
+        import["CreationDate"] = generated_at.clone().into();
+    }
+
+    serde_json::to_string_pretty(&import).expect("winget import serializes to JSON")
+}
+
+/// Returns the argv that reinstalls `record`, or `None` when its manager has no suitable command.
+fn install_command(record: &PackageRecord) -> Option<Vec<String>> {
+    let name = record.name.as_str();
+    let version =
+        Some(record.current_version.as_str()).filter(|version| *version != UNKNOWN_VERSION);
+    let argv = |words: &[&str]| Some(words.iter().map(|word| word.to_string()).collect());
+
+    match &record.manager {
+        PackageManager::Brew => argv(&["brew", "install", name]),
+        PackageManager::Npm => match version {
+            Some(version) => argv(&["npm", "install", "-g", &format!("{name}@{version}")]),
+            None => argv(&["npm", "install", "-g", name]),
+        },
+        PackageManager::Pnpm => match version {
+            Some(version) => argv(&["pnpm", "add", "-g", &format!("{name}@{version}")]),
+            None => argv(&["pnpm", "add", "-g", name]),
+        },
+        PackageManager::Pip => match version {
+            Some(version) => argv(&["pip", "install", &format!("{name}=={version}")]),
+            None => argv(&["pip", "install", name]),
+        },
+        PackageManager::Gem => match version {
+            Some(version) => argv(&["gem", "install", name, "-v", version]),
+            None => argv(&["gem", "install", name]),
+        },
+        PackageManager::Composer => match version {
+            Some(version) => argv(&[
+                "composer",
+                "global",
+                "require",
+                &format!("{name}:{version}"),
+            ]),
+            None => argv(&["composer", "global", "require", name]),
+        },
+        PackageManager::Choco => match version {
+            Some(version) => argv(&["choco", "install", name, "--version", version, "-y"]),
+            None => argv(&["choco", "install", name, "-y"]),
+        },
+        PackageManager::DotnetTool => match version {
+            Some(version) => argv(&[
+                "dotnet",
+                "tool",
+                "install",
+                "--global",
+                name,
+                "--version",
+                version,
+            ]),
+            None => argv(&["dotnet", "tool", "install", "--global", name]),
+        },
+        PackageManager::Opam => match version {
+            Some(version) => argv(&["opam", "install", "-y", &format!("{name}.{version}")]),
+            None => argv(&["opam", "install", "-y", name]),
+        },
+        PackageManager::LuaRocks => match version {
+            Some(version) => argv(&["luarocks", "install", name, version]),
+            None => argv(&["luarocks", "install", name]),
+        },
+        PackageManager::Asdf => {
+            version.and_then(|version| argv(&["asdf", "install", name, version]))
+        }
+        PackageManager::Flatpak => argv(&["flatpak", "install", "-y", name]),
+        PackageManager::Snap => argv(&["snap", "install", name]),
+        // The app id, not the name, identifies a Mac App Store app.
+        PackageManager::Mas => record
+            .source
+            .as_deref()
+            .and_then(|id| argv(&["mas", "install", id])),
+        PackageManager::Pacman => argv(&["pacman", "-S", "--needed", name]),
+        PackageManager::Zypper => argv(&["zypper", "install", name]),
+        PackageManager::Dnf => argv(&["dnf", "install", name]),
+        PackageManager::Apk => argv(&["apk", "add", name]),
+        PackageManager::MacPorts => argv(&["port", "install", name]),
+        PackageManager::Nix => argv(&["nix", "profile", "install", &format!("nixpkgs#{name}")]),
+        PackageManager::Vcpkg => match record.source.as_deref() {
+            Some(triplet) => argv(&["vcpkg", "install", &format!("{name}:{triplet}")]),
+            None => argv(&["vcpkg", "install", name]),
+        },
+        PackageManager::Rustup
+        | PackageManager::Cabal
+        | PackageManager::Hex
+        | PackageManager::Other(_) => None,
+    }
+}
+
+/// Quotes `word` for `sh` unless it only contains characters that never need quoting.
+fn shell_word(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_#-".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+fn truncate_cell(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_string();
+    }
+    let mut cut: String = value.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        to_csv, to_csv_with, to_install_script, to_json, to_markdown, to_markdown_with, to_table,
+        to_winget_import, write_json, write_split, ExportField, ExportFormat, ExportOptions,
+    };
+    use crate::{demo_snapshot, InventorySnapshot, PackageManager, PackageRecord};
+
+    #[test]
+    fn csv_has_header_and_one_row_per_package() {
+        let csv = to_csv(&demo_snapshot(), ExportField::DEFAULT);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "manager,name,current_version,latest_version,installed_at,status,source"
+        );
+        assert_eq!(
+            lines[1],
+            "brew,wget,1.24.5,1.24.6,2024-09-17T08:22:00Z,outdated,"
+        );
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn csv_quotes_cells_with_separators() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages[0].name = "odd,\"name\"".into();
+
+        assert!(
+            to_csv(&snapshot, ExportField::DEFAULT).contains("brew,\"odd,\"\"name\"\"\",1.24.5")
+        );
+    }
+
+    #[test]
+    fn csv_emits_selected_fields_in_order() {
+        let fields = [
+            ExportField::Status,
+            ExportField::Name,
+            ExportField::Dependency,
+        ];
+        let csv = to_csv(&demo_snapshot(), &fields);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "status,name,dependency");
+        assert_eq!(lines[1], "outdated,wget,false");
+    }
+
+    #[test]
+    fn markdown_table_uses_selected_fields() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages[0].name = "a|b".into();
+
+        let markdown = to_markdown(&snapshot, &[ExportField::Name, ExportField::LatestVersion]);
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines[0], "| name | latest_version |");
+        assert_eq!(lines[1], "| --- | --- |");
+        assert_eq!(lines[2], "| a\\|b | 1.24.6 |");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn installed_at_is_shown_at_the_requested_offset() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages.truncate(2);
+        snapshot.packages[1].installed_at = Some("last tuesday".into());
+        let options = ExportOptions {
+            utc_offset: Some(time::UtcOffset::from_hms(2, 0, 0).unwrap()),
+        };
+        let fields = [ExportField::Name, ExportField::InstalledAt];
+
+        assert_eq!(
+            to_csv_with(&snapshot, &fields, &options),
+            "name,installed_at\nwget,2024-09-17T10:22:00+02:00\ntypescript,last tuesday\n"
+        );
+        assert!(to_markdown_with(&snapshot, &fields, &options)
+            .contains("| wget | 2024-09-17T10:22:00+02:00 |"));
+        assert_eq!(
+            snapshot.packages[0].installed_at.as_deref(),
+            Some("2024-09-17T08:22:00Z")
+        );
+        assert!(to_csv(&snapshot, &fields).contains("wget,2024-09-17T08:22:00Z"));
+    }
+
+    #[test]
+    fn table_aligns_columns_to_widest_cell() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages.truncate(2);
+
+        assert_eq!(
+            to_table(&snapshot),
+            "MANAGER  NAME        CURRENT  LATEST  STATUS\n\
+             brew     wget        1.24.5   1.24.6  outdated\n\
+             npm      typescript  5.5.2    5.6.3   current\n"
+        );
+    }
+
+    #[test]
+    fn table_truncates_long_names() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages.truncate(1);
+        snapshot.packages[0].name = "x".repeat(50);
+
+        let table = to_table(&snapshot);
+        let row = table.lines().nth(1).unwrap();
+
+        assert!(row.starts_with(&format!("brew     {}…  1.24.5", "x".repeat(39))));
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let mut buffer = Vec::new();
+        write_json(&demo_snapshot(), &mut buffer).unwrap();
+
+        let parsed: InventorySnapshot = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed, demo_snapshot());
+    }
+
+    #[test]
+    fn json_is_compact_or_pretty_on_request() {
+        let compact = to_json(&demo_snapshot(), false);
+        let pretty = to_json(&demo_snapshot(), true);
+
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains('\n'));
+        let mut written = Vec::new();
+        write_json(&demo_snapshot(), &mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), pretty);
+        assert_eq!(
+            serde_json::from_str::<InventorySnapshot>(&compact).unwrap(),
+            serde_json::from_str::<InventorySnapshot>(&pretty).unwrap()
+        );
+    }
+
+    #[test]
+    fn write_split_writes_one_file_per_manager() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut snapshot = demo_snapshot();
+        snapshot.push(
+            PackageRecord::builder("jq", PackageManager::Brew)
+                .current_version("1.7.1")
+                .build(),
+        );
+
+        write_split(&snapshot, dir.path(), ExportFormat::Json).unwrap();
+
+        let mut files: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["brew.json", "npm.json", "pip.json"]);
+        let brew: InventorySnapshot =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("brew.json")).unwrap())
+                .unwrap();
+        assert_eq!(brew.generated_at, snapshot.generated_at);
+        let names: Vec<&str> = brew
+            .packages
+            .iter()
+            .map(|record| record.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["wget", "jq"]);
+
+        write_split(&demo_snapshot(), dir.path(), ExportFormat::Csv).unwrap();
+        let csv = std::fs::read_to_string(dir.path().join("npm.csv")).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().starts_with("npm,typescript,"));
+    }
+
+    #[test]
+    fn write_split_rejects_manager_names_outside_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["../escape", "/etc/cron.d/bagpack", "..", "nested\\name"] {
+            let mut snapshot = demo_snapshot();
+            snapshot
+                .push(PackageRecord::builder("tool", PackageManager::Other(name.into())).build());
+
+            let err = write_split(&snapshot, dir.path(), ExportFormat::Json).unwrap_err();
+
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{name}");
+        }
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        assert!(!dir.path().parent().unwrap().join("escape.json").exists());
+    }
+
+    #[test]
+    fn winget_import_lists_only_winget_records_by_source() {
+        let winget = PackageManager::Other("winget".into());
+        let mut snapshot = demo_snapshot();
+        snapshot.push(
+            PackageRecord::builder("Git.Git", winget.clone())
+                .current_version("2.43.0")
+                .build(),
+        );
+        snapshot.push(
+            PackageRecord::builder("9NBLGGH4NNS1", winget.clone())
+                .source("msstore")
+                .build(),
+        );
+        snapshot.push(PackageRecord::builder("Microsoft.PowerToys", winget).build());
+
+        let import: serde_json::Value = serde_json::from_str(&to_winget_import(&snapshot)).unwrap();
+
+        assert_eq!(
+            import["$schema"],
+            "https://aka.ms/winget-packages.schema.2.0.json"
+        );
+        assert_eq!(import["CreationDate"], "2025-10-05T12:34:56Z");
+        let sources = import["Sources"].as_array().unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0]["SourceDetails"]["Name"], "winget");
+        assert_eq!(
+            sources[0]["Packages"],
+            serde_json::json!([
+                {"PackageIdentifier": "Git.Git", "Version": "2.43.0"},
+                {"PackageIdentifier": "Microsoft.PowerToys"},
+            ])
+        );
+        assert_eq!(sources[1]["SourceDetails"]["Identifier"], "StoreEdgeFD");
+        assert_eq!(
+            sources[1]["Packages"],
+            serde_json::json!([{"PackageIdentifier": "9NBLGGH4NNS1"}])
+        );
+
+        let empty: serde_json::Value =
+            serde_json::from_str(&to_winget_import(&demo_snapshot())).unwrap();
+        assert_eq!(empty["Sources"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn install_script_pins_versions_per_manager() {
+        let mut snapshot = demo_snapshot();
+        snapshot.push(
+            PackageRecord::builder("jq", PackageManager::Brew)
+                .current_version("1.7.1")
+                .dependency(true)
+                .build(),
+        );
+        snapshot.push(
+            PackageRecord::builder("Xcode", PackageManager::Mas)
+                .current_version("15.3")
+                .source("497799835")
+                .build(),
+        );
+        snapshot.push(
+            PackageRecord::builder("cargo-x86_64-unknown-linux-gnu", PackageManager::Rustup)
+                .build(),
+        );
+        snapshot.push(PackageRecord::builder("it's", PackageManager::Pip).build());
+
+        assert_eq!(
+            to_install_script(&snapshot),
+            "#!/bin/sh\n\
+             set -e\n\
+             \n\
+             # brew\n\
+             brew install wget\n\
+             \n\
+             # npm\n\
+             npm install -g typescript@5.5.2\n\
+             \n\
+             # pip\n\
+             pip install requests==2.32.3\n\
+             pip install 'it'\\''s'\n\
+             \n\
+             # rustup\n\
+             # not reinstallable: cargo-x86_64-unknown-linux-gnu\n\
+             \n\
+             # mas\n\
+             mas install 497799835\n"
+        );
+    }
+}
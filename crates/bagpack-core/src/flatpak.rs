@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus, UNKNOWN_VERSION,
+};
+
+pub(crate) fn collect_flatpak(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "flatpak",
+        &["list", "--app", "--columns=application,version"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(
+        &list_output,
+        "flatpak list --app --columns=application,version",
+    )?;
+
+    let installed = parse_columns(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let updates_output = run_command(
+        options,
+        "flatpak",
+        &[
+            "remote-ls",
+            "--updates",
+            "--app",
+            "--columns=application,version",
+        ],
+        None::<&[i32]>,
+    )?;
+    ensure_success(
+        &updates_output,
+        "flatpak remote-ls --updates --app --columns=application,version",
+    )?;
+
+    let updates: HashMap<String, String> =
+        parse_columns(&updates_output.stdout).into_iter().collect();
+
+    Ok(build_records(installed, &updates))
+}
+
+/// Parses `application<TAB>version` rows, skipping the header flatpak prints on a TTY.
+///
+/// Apps that do not publish a version keep an empty string so the caller can decide how to
+/// present them.
+fn parse_columns(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut columns = line.split('\t').map(str::trim);
+            let application = columns.next().filter(|id| !id.is_empty())?;
+            let version = columns.next().unwrap_or_default();
+            Some((application.to_string(), version.to_string()))
+        })
+        .filter(|(application, _)| application != "Application ID")
+        .collect()
+}
+
+fn build_records(
+    installed: Vec<(String, String)>,
+    updates: &HashMap<String, String>,
+) -> Vec<PackageRecord> {
+    installed
+        .into_iter()
+        .map(|(name, version)| {
+            let current_version = if version.is_empty() {
+                UNKNOWN_VERSION.to_string()
+            } else {
+                version
+            };
+
+            // `remote-ls --updates` lists an app whenever a newer commit exists, even if the
+            // published version string did not change.
+            let (latest_version, status) = match updates.get(&name) {
+                Some(latest) if !latest.is_empty() => {
+                    (Some(latest.clone()), PackageStatus::Outdated)
+                }
+                Some(_) => (None, PackageStatus::Outdated),
+                None => (None, PackageStatus::Current),
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Flatpak,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_columns};
+    use crate::PackageStatus;
+    use std::collections::HashMap;
+
+    #[test]
+    fn flatpak_columns_parse_tab_separated_rows() {
+        let stdout = "Application ID\tVersion\n\
+                      org.mozilla.firefox\t128.0.3\n\
+                      com.valvesoftware.Steam\t\n\
+                      \n\
+                      org.gnome.Calculator\t46.1 \n";
+
+        assert_eq!(
+            parse_columns(stdout),
+            vec![
+                ("org.mozilla.firefox".to_string(), "128.0.3".to_string()),
+                ("com.valvesoftware.Steam".to_string(), String::new()),
+                ("org.gnome.Calculator".to_string(), "46.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn flatpak_updates_mark_records_outdated() {
+        let installed = parse_columns("org.mozilla.firefox\t128.0.3\ncom.valvesoftware.Steam\t\n");
+        let updates = HashMap::from([("org.mozilla.firefox".to_string(), "129.0".to_string())]);
+
+        let records = build_records(installed, &updates);
+
+        assert_eq!(records[0].status, PackageStatus::Outdated);
+        assert_eq!(records[0].latest_version.as_deref(), Some("129.0"));
+        assert_eq!(records[1].status, PackageStatus::Current);
+        assert_eq!(records[1].current_version, "unknown");
+    }
+}
@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{
+    compare_versions, ensure_success, run_command, CollectInventoryOptions, CollectionError,
+    PackageManager, PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_gem(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    // `--details` reports the gem home each version is installed in, which separates
+    // system-wide installs from `--user-install` ones without querying each scope.
+    let list_output = run_command(
+        options,
+        "gem",
+        &["list", "--local", "--details"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "gem list --local --details")?;
+
+    let installed = parse_details(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(options, "gem", &["outdated"], None::<&[i32]>)?;
+    ensure_success(&outdated_output, "gem outdated")?;
+
+    Ok(build_records(
+        installed,
+        &parse_outdated(&outdated_output.stdout),
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct InstalledGem {
+    name: String,
+    version: String,
+    gem_home: String,
+}
+
+/// Parses `gem list --local --details`, yielding the newest version of each gem per gem home.
+///
+/// Each gem starts with an unindented `name (1.2.0, 1.1.0)` line. Its `Installed at` detail is
+/// either `Installed at: <home>` for every listed version, or one `(<version>): <home>` entry
+/// per version, the first on the `Installed at` line and the rest on indented lines below it.
+fn parse_details(stdout: &str) -> Vec<InstalledGem> {
+    let mut gems: Vec<InstalledGem> = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    let mut in_installed_at = false;
+
+    let mut add = |name: &str, version: &str, gem_home: &str| {
+        let existing = gems
+            .iter_mut()
+            .find(|gem| gem.name == name && gem.gem_home == gem_home);
+        match existing {
+            Some(gem) => {
+                if compare_versions(version, &gem.version) == Ordering::Greater {
+                    gem.version = version.to_string();
+                }
+            }
+            None => gems.push(InstalledGem {
+                name: name.to_string(),
+                version: version.to_string(),
+                gem_home: gem_home.to_string(),
+            }),
+        }
+    };
+
+    for line in stdout.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            in_installed_at = false;
+            current = parse_header(line);
+            continue;
+        }
+        let Some((name, versions)) = &current else {
+            continue;
+        };
+
+        let detail = line.trim();
+        let entry = match detail.strip_prefix("Installed at") {
+            Some(rest) => {
+                in_installed_at = true;
+                rest.trim_start()
+            }
+            None if in_installed_at && detail.starts_with('(') => detail,
+            None => {
+                in_installed_at = false;
+                continue;
+            }
+        };
+
+        match entry.strip_prefix(':') {
+            Some(gem_home) => {
+                for version in versions {
+                    add(name, version, gem_home.trim());
+                }
+            }
+            None => {
+                let Some((label, gem_home)) = entry
+                    .strip_prefix('(')
+                    .and_then(|entry| entry.split_once("):"))
+                else {
+                    continue;
+                };
+                // The label is `1.2.0`, `1.2.0, default`, or just `default` for a gem with a
+                // single default version.
+                let version = label.split(',').next().unwrap_or(label).trim();
+                let version = if version == "default" {
+                    versions.first().map(String::as_str).unwrap_or(version)
+                } else {
+                    version
+                };
+                add(name, version, gem_home.trim());
+            }
+        }
+    }
+
+    gems
+}
+
+/// Parses `rake (13.1.0, default: 13.0.6)` into the name and its versions.
+fn parse_header(line: &str) -> Option<(String, Vec<String>)> {
+    let (name, versions) = line.trim().split_once(" (")?;
+    let versions = versions.strip_suffix(')')?;
+    let versions = versions
+        .split(',')
+        .map(|version| {
+            let version = version.trim();
+            version.strip_prefix("default:").unwrap_or(version).trim()
+        })
+        .filter(|version| !version.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some((name.to_string(), versions))
+}
+
+/// Parses `gem outdated` lines shaped like `rake (13.0.6 < 13.1.0)`.
+fn parse_outdated(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, versions) = line.trim().split_once(" (")?;
+            let (_, latest) = versions.strip_suffix(')')?.split_once(" < ")?;
+            Some((name.to_string(), latest.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Turns installed gems into records tagged with their gem home.
+///
+/// `gem outdated` reports one latest version per name, so a copy in another gem home that is
+/// already at that version stays current.
+fn build_records(
+    gems: Vec<InstalledGem>,
+    outdated: &HashMap<String, String>,
+) -> Vec<PackageRecord> {
+    gems.into_iter()
+        .map(|gem| {
+            let latest_version = outdated
+                .get(&gem.name)
+                .filter(|latest| compare_versions(latest, &gem.version) == Ordering::Greater)
+                .cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name: gem.name,
+                current_version: gem.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Gem,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: Some(gem.gem_home),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_details, parse_outdated, InstalledGem};
+    use crate::PackageStatus;
+
+    const DETAILS: &str = "\
+bundler (2.5.6, default: 2.4.19)
+    Author: André Arko, Samuel Giddins
+    Homepage: https://bundler.io
+    License: MIT
+    Installed at (2.5.6): /home/dev/.local/share/gem/ruby/3.2.0
+                 (2.4.19, default): /usr/lib/ruby/gems/3.2.0
+
+    The best way to manage your application's dependencies
+
+rake (13.0.6)
+    Author: Hiroshi SHIBATA, Eric Hodel, Jim Weirich
+    License: MIT
+    Installed at (default): /usr/lib/ruby/gems/3.2.0
+
+    Rake is a Make-like program implemented in Ruby
+
+rubocop (1.62.1, 1.60.0)
+    Author: Bozhidar Batsov, Jonas Arvidsson, Yuji Nakayama
+    Installed at: /home/dev/.local/share/gem/ruby/3.2.0
+
+    RuboCop is a Ruby code style checking and code formatting tool.
+";
+
+    #[test]
+    fn gem_details_split_versions_by_gem_home() {
+        let user = "/home/dev/.local/share/gem/ruby/3.2.0";
+        let system = "/usr/lib/ruby/gems/3.2.0";
+        let gem = |name: &str, version: &str, gem_home: &str| InstalledGem {
+            name: name.into(),
+            version: version.into(),
+            gem_home: gem_home.into(),
+        };
+
+        assert_eq!(
+            parse_details(DETAILS),
+            vec![
+                gem("bundler", "2.5.6", user),
+                gem("bundler", "2.4.19", system),
+                gem("rake", "13.0.6", system),
+                gem("rubocop", "1.62.1", user),
+            ]
+        );
+    }
+
+    #[test]
+    fn gem_records_carry_gem_home_and_outdated_status() {
+        let outdated = parse_outdated("bundler (2.5.6 < 2.5.7)\nrake (13.0.6 < 13.1.0)\n");
+
+        let records = build_records(parse_details(DETAILS), &outdated);
+
+        assert_eq!(
+            records[0].source.as_deref(),
+            Some("/home/dev/.local/share/gem/ruby/3.2.0")
+        );
+        assert_eq!(records[0].latest_version.as_deref(), Some("2.5.7"));
+        assert_eq!(
+            records[1].source.as_deref(),
+            Some("/usr/lib/ruby/gems/3.2.0")
+        );
+        assert_eq!(records[1].status, PackageStatus::Outdated);
+        assert_eq!(records[2].status, PackageStatus::Outdated);
+        assert_eq!(records[3].status, PackageStatus::Current);
+    }
+}
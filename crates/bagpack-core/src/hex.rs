@@ -0,0 +1,157 @@
+use crate::{
+    ensure_success, run_command, run_command_in, CollectInventoryOptions, CollectionError,
+    PackageManager, PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_hex(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let archive_output = run_command(options, "mix", &["archive"], None::<&[i32]>)?;
+    ensure_success(&archive_output, "mix archive")?;
+
+    // Archives have no bulk outdated query, so their status stays unknown.
+    let mut packages: Vec<PackageRecord> = parse_archives(&archive_output.stdout)
+        .into_iter()
+        .map(|(name, current_version)| PackageRecord {
+            name,
+            current_version,
+            latest_version: None,
+            installed_at: None,
+            status: PackageStatus::Unknown,
+            manager: PackageManager::Hex,
+            pinned: false,
+            dependency: false,
+            linked: false,
+            source: None,
+            homepage: None,
+            deprecated: None,
+            provenance: None,
+            download_size: None,
+        })
+        .collect();
+
+    if let Some(project) = &options.hex_project {
+        // Recent Hex releases exit with 1 when any dependency is outdated.
+        let outdated_output =
+            run_command_in(options, Some(project), "mix", &["hex.outdated"], Some(&[1]))?;
+        ensure_success(&outdated_output, "mix hex.outdated")?;
+
+        let source = project.display().to_string();
+        packages.extend(
+            parse_outdated(&outdated_output.stdout)
+                .into_iter()
+                .map(|dependency| {
+                    let status = if dependency.latest == dependency.current {
+                        PackageStatus::Current
+                    } else {
+                        PackageStatus::Outdated
+                    };
+
+                    PackageRecord {
+                        name: dependency.name,
+                        current_version: dependency.current,
+                        latest_version: Some(dependency.latest),
+                        installed_at: None,
+                        status,
+                        manager: PackageManager::Hex,
+                        pinned: false,
+                        dependency: true,
+                        linked: false,
+                        source: Some(source.clone()),
+                        homepage: None,
+                        deprecated: None,
+                        provenance: None,
+                        download_size: None,
+                    }
+                }),
+        );
+    }
+
+    Ok(packages)
+}
+
+/// Parses `mix archive` rows like `* phx_new-1.7.10`; the trailing
+/// `Archives installed at: ...` line is skipped.
+fn parse_archives(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let archive = line.trim().strip_prefix("* ")?;
+            let (name, version) = archive.rsplit_once('-')?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct HexDependency {
+    name: String,
+    current: String,
+    latest: String,
+}
+
+/// Parses the `Dependency Current Latest Status` table printed by `mix hex.outdated`.
+///
+/// The table ends at the first blank line; the hint text after it is ignored.
+fn parse_outdated(stdout: &str) -> Vec<HexDependency> {
+    stdout
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("Dependency"))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            Some(HexDependency {
+                name: columns.next()?.to_string(),
+                current: columns.next()?.to_string(),
+                latest: columns.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_archives, parse_outdated, HexDependency};
+
+    #[test]
+    fn hex_archives_split_name_and_version() {
+        let stdout = "* hex-2.0.6\n\
+                      * phx_new-1.7.10\n\
+                      Archives installed at: /home/dev/.mix/archives\n";
+
+        assert_eq!(
+            parse_archives(stdout),
+            vec![
+                ("hex".to_string(), "2.0.6".to_string()),
+                ("phx_new".to_string(), "1.7.10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_outdated_reads_dependency_table() {
+        let stdout = "Dependency  Current  Latest  Status\n\
+                      jason       1.4.0    1.4.1   Update possible\n\
+                      phoenix     1.7.10   1.7.10  Up-to-date\n\
+                      \n\
+                      Run `mix hex.outdated APP` to see requirements for a specific dependency.\n";
+
+        assert_eq!(
+            parse_outdated(stdout),
+            vec![
+                HexDependency {
+                    name: "jason".into(),
+                    current: "1.4.0".into(),
+                    latest: "1.4.1".into(),
+                },
+                HexDependency {
+                    name: "phoenix".into(),
+                    current: "1.7.10".into(),
+                    latest: "1.7.10".into(),
+                },
+            ]
+        );
+        assert!(parse_outdated("").is_empty());
+    }
+}
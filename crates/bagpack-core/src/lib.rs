@@ -1,12 +1,90 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus};
+use std::str::FromStr;
+use std::sync::{mpsc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
+mod apk;
+mod asdf;
+mod builder;
+mod cabal;
+mod cancel;
+mod choco;
+mod composer;
+mod demo;
+mod detect;
+mod diff;
+mod dnf;
+mod dotnet_tool;
+mod export;
+mod flatpak;
+mod gem;
+mod hex;
+mod luarocks;
+mod macports;
+mod manifest;
+mod mas;
+mod nix;
+mod opam;
+mod pacman;
+mod pattern;
+mod pnpm;
+mod rustup;
+mod sbom;
+#[cfg(feature = "schemars")]
+mod schema;
+mod sink;
+mod snap;
+mod upgrade;
+mod validate;
+mod vcpkg;
+mod version;
+mod watch;
+#[cfg(feature = "zypper")]
+mod zypper;
+
+pub use builder::PackageRecordBuilder;
+pub use cancel::CancellationToken;
+pub use demo::demo_snapshot;
+pub use detect::{
+    detect_available_managers, detect_available_managers_with, detect_python_environments,
+    detect_python_environments_with,
+};
+pub use diff::{diff_snapshots, diff_to_markdown, SnapshotDiff, VersionChange};
+pub use export::{
+    to_csv, to_csv_with, to_install_script, to_json, to_markdown, to_markdown_with, to_table,
+    to_winget_import, write_json, write_split, ExportField, ExportFormat, ExportOptions,
+};
+pub use manifest::{
+    check_against_manifest, Manifest, ManifestEntry, ManifestError, ManifestReport, VersionMismatch,
+};
+pub use sbom::{to_cyclonedx, to_spdx};
+#[cfg(feature = "schemars")]
+pub use schema::json_schema;
+#[cfg(feature = "network")]
+pub use sink::HttpSink;
+pub use sink::{SinkError, SnapshotSink};
+pub use upgrade::{upgrade_manager, upgrade_plan, UpgradeResult};
+pub use validate::ValidationIssue;
+pub use version::{compare_versions, normalize_version, UpdateDebt, UpdateKind};
+pub use watch::{watch, WatchHandle, WatchOptions};
+
+/// Placeholder that replaces package names matched by a redaction pattern.
+pub const REDACTED_NAME: &str = "<redacted>";
+
+/// Placeholder stored in `current_version` when a manager does not report one.
+pub const UNKNOWN_VERSION: &str = "unknown";
+
 /// Canonical representation of a package across supported managers.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PackageRecord {
     pub name: String,
     pub current_version: String,
@@ -14,13 +92,46 @@ pub struct PackageRecord {
     pub installed_at: Option<String>,
     pub status: PackageStatus,
     pub manager: PackageManager,
+    /// Whether the manager holds the package at its current version, e.g. a pinned Chocolatey
+    /// package. Pinned packages keep their outdated status and latest version.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Whether the package was installed only as a dependency of another package, such as a
+    /// Homebrew formula missing from `brew leaves`.
+    #[serde(default)]
+    pub dependency: bool,
+    /// Whether the package is symlinked to a local checkout (e.g. via `npm link`).
+    #[serde(default)]
+    pub linked: bool,
+    /// Manager-specific origin of the record, such as a Mac App Store app id.
+    pub source: Option<String>,
+    /// Project homepage, filled in only when [`CollectInventoryOptions::homepages`] is set and
+    /// the manager reports one (currently Homebrew).
+    #[serde(default)]
+    pub homepage: Option<String>,
+    /// Upstream deprecation notice, filled in only when
+    /// [`CollectInventoryOptions::deprecations`] is set (currently npm).
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    /// Commands the record's data came from, in run order and separated by `; `, filled in only
+    /// when [`CollectInventoryOptions::record_provenance`] is set.
+    #[serde(default)]
+    pub provenance: Option<String>,
+    /// Bytes an upgrade would download, filled in only when
+    /// [`CollectInventoryOptions::download_sizes`] is set (currently outdated Homebrew formulae).
+    #[serde(default)]
+    pub download_size: Option<u64>,
 }
 
 /// Snapshot-level metadata plus manager inventory.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InventorySnapshot {
     pub generated_at: Option<String>,
     pub packages: Vec<PackageRecord>,
+    /// Package count of the full snapshot when this snapshot is a [`page`](Self::page) of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
 }
 
 /// Summary returned to UIs, including non-fatal collection warnings.
@@ -28,28 +139,290 @@ pub struct InventorySnapshot {
 pub struct CollectionSummary {
     pub snapshot: InventorySnapshot,
     pub warnings: Vec<CollectionWarning>,
+    /// Managers collection was attempted for, in execution order.
+    #[serde(default)]
+    pub attempted_managers: Vec<PackageManager>,
+    /// Raw stdout of every command each manager ran, in run order, when
+    /// [`CollectInventoryOptions::capture_raw`] is set.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub raw_outputs: HashMap<PackageManager, Vec<String>>,
+    /// Wall-clock time each attempted manager's collector took, including failed ones.
+    #[serde(default)]
+    pub durations: HashMap<PackageManager, Duration>,
+    /// Whether [`CollectInventoryOptions::cancel`] stopped the collection early. Managers that
+    /// were skipped or interrupted have no records, warning, or duration, and are left out of
+    /// `attempted_managers`.
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CollectionWarning {
     pub manager: PackageManager,
     pub message: String,
+    /// How many identical warnings this one stands for after
+    /// [`CollectionSummary::dedupe_warnings`]; `1` is omitted from JSON.
+    #[serde(default = "single_warning", skip_serializing_if = "is_single_warning")]
+    pub count: usize,
+}
+
+fn single_warning() -> usize {
+    1
+}
+
+fn is_single_warning(count: &usize) -> bool {
+    *count == 1
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Update state of a package.
+///
+/// Variants order by declaration (`Current < Ahead < Outdated < Unknown`); keep new variants
+/// in the intended sort position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum PackageStatus {
     Current,
+    /// The installed version is newer than the latest one the manager reports, e.g. when that
+    /// release was yanked or a pre-release is installed.
+    Ahead,
     Outdated,
     Unknown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Package manager a record was collected from.
+///
+/// Variants order by declaration, starting with the original Homebrew, npm, and pip trio, so
+/// grouped and sorted output lists managers in that order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PackageManager {
     Brew,
     Npm,
     Pip,
+    Flatpak,
+    Snap,
+    Rustup,
+    Mas,
+    Composer,
+    DotnetTool,
+    Nix,
+    Pacman,
+    Zypper,
+    Asdf,
+    #[serde(rename = "macports")]
+    MacPorts,
+    Opam,
+    Choco,
+    Gem,
+    Cabal,
+    Hex,
+    Apk,
+    Dnf,
+    #[serde(rename = "luarocks")]
+    LuaRocks,
+    Vcpkg,
+    Pnpm,
+    /// A manager supplied by a custom [`Collector`], serialized as its bare name.
+    ///
+    /// Names of built-in managers deserialize to their own variants instead.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl PackageStatus {
+    /// Returns the serialized (snake_case) name of the status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageStatus::Current => "current",
+            PackageStatus::Ahead => "ahead",
+            PackageStatus::Outdated => "outdated",
+            PackageStatus::Unknown => "unknown",
+        }
+    }
+}
+
+impl PackageManager {
+    /// Every built-in manager, in declaration order; [`PackageManager::Other`] is not included.
+    pub const ALL: &'static [PackageManager] = &[
+        PackageManager::Brew,
+        PackageManager::Npm,
+        PackageManager::Pip,
+        PackageManager::Flatpak,
+        PackageManager::Snap,
+        PackageManager::Rustup,
+        PackageManager::Mas,
+        PackageManager::Composer,
+        PackageManager::DotnetTool,
+        PackageManager::Nix,
+        PackageManager::Pacman,
+        PackageManager::Zypper,
+        PackageManager::Asdf,
+        PackageManager::MacPorts,
+        PackageManager::Opam,
+        PackageManager::Choco,
+        PackageManager::Gem,
+        PackageManager::Cabal,
+        PackageManager::Hex,
+        PackageManager::Apk,
+        PackageManager::Dnf,
+        PackageManager::LuaRocks,
+        PackageManager::Vcpkg,
+        PackageManager::Pnpm,
+    ];
+
+    /// Returns the serialized (snake_case) name of the manager.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PackageManager::Brew => "brew",
+            PackageManager::Npm => "npm",
+            PackageManager::Pip => "pip",
+            PackageManager::Flatpak => "flatpak",
+            PackageManager::Snap => "snap",
+            PackageManager::Rustup => "rustup",
+            PackageManager::Mas => "mas",
+            PackageManager::Composer => "composer",
+            PackageManager::DotnetTool => "dotnet_tool",
+            PackageManager::Nix => "nix",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Asdf => "asdf",
+            PackageManager::MacPorts => "macports",
+            PackageManager::Opam => "opam",
+            PackageManager::Choco => "choco",
+            PackageManager::Gem => "gem",
+            PackageManager::Cabal => "cabal",
+            PackageManager::Hex => "hex",
+            PackageManager::Apk => "apk",
+            PackageManager::Dnf => "dnf",
+            PackageManager::LuaRocks => "luarocks",
+            PackageManager::Vcpkg => "vcpkg",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Other(name) => name,
+        }
+    }
+
+    /// Returns the name of the CLI binary the manager's collector runs.
+    pub(crate) fn program(&self) -> &str {
+        match self {
+            PackageManager::DotnetTool => "dotnet",
+            PackageManager::MacPorts => "port",
+            PackageManager::Hex => "mix",
+            other => other.as_str(),
+        }
+    }
+}
+
+impl FromStr for PackageManager {
+    type Err = UnknownManagerError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        PackageManager::ALL
+            .iter()
+            .find(|manager| manager.as_str() == value)
+            .cloned()
+            .ok_or_else(|| UnknownManagerError(value.to_string()))
+    }
+}
+
+impl PackageRecord {
+    /// Returns whether both records describe the same package, i.e. share `manager` and `name`,
+    /// regardless of versions and volatile fields such as `installed_at`.
+    pub fn same_identity(&self, other: &PackageRecord) -> bool {
+        self.manager == other.manager && self.name == other.name
+    }
+
+    /// Returns whether both records have the same installed version.
+    ///
+    /// `latest_version` is not compared, since it changes with upstream releases rather than with
+    /// what is installed.
+    pub fn same_version(&self, other: &PackageRecord) -> bool {
+        self.current_version == other.current_version
+    }
+
+    /// Classifies the available update, or `None` when no newer `latest_version` is known.
+    pub fn update_kind(&self) -> Option<UpdateKind> {
+        let latest = self.latest_version.as_deref()?;
+        version::update_kind(&self.current_version, latest)
+    }
+
+    /// Re-derives `status` from the versions, e.g. for records loaded from an older snapshot.
+    ///
+    /// A `latest_version` newer than `current_version` (by [`compare_versions`]) is outdated
+    /// even when the record is `pinned`, matching the collectors; any other known latest version
+    /// is current. An unknown current version or a linked checkout is unknown, and without a
+    /// latest version the status is left as it was.
+    pub fn recompute_status(&mut self) {
+        if self.current_version == UNKNOWN_VERSION || self.linked {
+            self.status = PackageStatus::Unknown;
+            return;
+        }
+        if let Some(latest) = &self.latest_version {
+            self.status = match compare_versions(latest, &self.current_version) {
+                std::cmp::Ordering::Greater => PackageStatus::Outdated,
+                std::cmp::Ordering::Less => PackageStatus::Ahead,
+                std::cmp::Ordering::Equal => PackageStatus::Current,
+            };
+        }
+    }
+
+    /// Returns the argv that upgrades only this package, or `None` when it is not outdated or
+    /// its manager has no per-package upgrade in [`upgrade_plan`].
+    pub fn upgrade_command(&self) -> Option<Vec<String>> {
+        upgrade::package_upgrade_command(self)
+    }
+
+    /// Describes the record's update state in one human-readable sentence.
+    pub fn explain(&self) -> String {
+        match (self.status, self.latest_version.as_deref()) {
+            (PackageStatus::Outdated, Some(latest)) => {
+                let kind = self
+                    .update_kind()
+                    .map(|kind| format!(" ({} update)", kind.as_str()))
+                    .unwrap_or_default();
+                format!(
+                    "{} is outdated: {} installed, {} available{}",
+                    self.name, self.current_version, latest, kind
+                )
+            }
+            (PackageStatus::Outdated, None) => format!(
+                "{} is outdated: {} installed, latest version unknown",
+                self.name, self.current_version
+            ),
+            (PackageStatus::Current, _) => {
+                format!("{} is up to date at {}", self.name, self.current_version)
+            }
+            (PackageStatus::Ahead, latest) => format!(
+                "{} is ahead of the published latest: {} installed, {} published",
+                self.name,
+                self.current_version,
+                latest.unwrap_or("unknown")
+            ),
+            (PackageStatus::Unknown, _) => format!(
+                "{}: latest version unknown ({} installed)",
+                self.name, self.current_version
+            ),
+        }
+    }
+
+    /// Returns whether the package was installed more than `threshold` ago.
+    ///
+    /// Records without a parseable `installed_at` are never stale.
+    pub fn is_stale(&self, threshold: Duration) -> bool {
+        self.is_stale_at(threshold, OffsetDateTime::now_utc())
+    }
+
+    /// Like [`PackageRecord::is_stale`], measured against `now` instead of the system clock.
+    pub fn is_stale_at(&self, threshold: Duration, now: OffsetDateTime) -> bool {
+        self.installed_at_time()
+            .is_some_and(|installed_at| now - installed_at > threshold)
+    }
+
+    fn installed_at_time(&self) -> Option<OffsetDateTime> {
+        self.installed_at
+            .as_deref()
+            .and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+    }
 }
 
 impl InventorySnapshot {
@@ -58,6 +431,17 @@ impl InventorySnapshot {
         self.packages.push(record);
     }
 
+    /// Returns the number of packages in the snapshot; for a [`page`](Self::page) this is the
+    /// page size, not `total`.
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    /// Returns whether the snapshot has no packages.
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
     /// Returns the number of packages flagged as outdated.
     pub fn outdated_count(&self) -> usize {
         self.packages
@@ -66,392 +450,2600 @@ impl InventorySnapshot {
             .count()
     }
 
-    /// Sets the generation timestamp using an ISO-8601 string.
-    pub fn set_generated_at(&mut self, iso_timestamp: impl Into<String>) {
-        self.generated_at = Some(iso_timestamp.into());
-    }
-}
-
-impl CollectionSummary {
-    pub fn new(snapshot: InventorySnapshot) -> Self {
-        Self {
-            snapshot,
-            warnings: Vec::new(),
+    /// Like [`outdated_count`](Self::outdated_count), per manager. Every manager in the snapshot
+    /// has an entry, `0` when none of its packages are outdated.
+    pub fn outdated_count_by_manager(&self) -> HashMap<PackageManager, usize> {
+        let mut counts: HashMap<PackageManager, usize> = HashMap::new();
+        for record in &self.packages {
+            let count = counts.entry(record.manager.clone()).or_default();
+            if record.status == PackageStatus::Outdated {
+                *count += 1;
+            }
         }
+        counts
     }
 
-    fn push_warning(&mut self, manager: PackageManager, error: CollectionError) {
-        self.warnings.push(CollectionWarning {
-            manager,
-            message: error.to_string(),
-        });
+    /// Checks the snapshot for internal inconsistencies, such as an outdated record without a
+    /// latest version, and returns every issue found.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        validate::validate(self)
     }
-}
-
-/// Gather package inventories from Homebrew, npm, and pip.
-///
-/// The function attempts each manager independently and records failures as warnings so that
-/// remaining data can still surface to the UI.
-pub fn collect_inventory() -> CollectionSummary {
-    let mut snapshot = InventorySnapshot::default();
 
-    if let Ok(timestamp) = OffsetDateTime::now_utc().format(&Rfc3339) {
-        snapshot.set_generated_at(timestamp);
+    /// Returns the record with the earliest `installed_at`, preferring the alphabetically first
+    /// name on ties.
+    ///
+    /// Records with a missing or unparseable `installed_at` are ignored.
+    pub fn oldest(&self) -> Option<&PackageRecord> {
+        self.timestamped()
+            .min_by(|(a_time, a), (b_time, b)| a_time.cmp(b_time).then_with(|| a.name.cmp(&b.name)))
+            .map(|(_, record)| record)
     }
 
-    let mut summary = CollectionSummary::new(snapshot);
+    /// Returns the record with the latest `installed_at`, preferring the alphabetically first
+    /// name on ties.
+    ///
+    /// Records with a missing or unparseable `installed_at` are ignored.
+    pub fn newest(&self) -> Option<&PackageRecord> {
+        self.timestamped()
+            .max_by(|(a_time, a), (b_time, b)| a_time.cmp(b_time).then_with(|| b.name.cmp(&a.name)))
+            .map(|(_, record)| record)
+    }
 
-    match collect_brew() {
-        Ok(packages) => summary.snapshot.packages.extend(packages),
-        Err(err) => summary.push_warning(PackageManager::Brew, err),
+    fn timestamped(&self) -> impl Iterator<Item = (OffsetDateTime, &PackageRecord)> {
+        self.packages
+            .iter()
+            .filter_map(|record| Some((record.installed_at_time()?, record)))
     }
 
-    match collect_npm() {
-        Ok(packages) => summary.snapshot.packages.extend(packages),
-        Err(err) => summary.push_warning(PackageManager::Npm, err),
+    /// Returns records whose name fuzzily matches `query`, best match first, with a score in
+    /// `(0.0, 1.0]` where `1.0` is an exact (case-insensitive) match.
+    ///
+    /// Substring matches rank above typo-tolerant ones; equal scores keep snapshot order.
+    pub fn search(&self, query: &str) -> Vec<(&PackageRecord, f32)> {
+        let mut matches: Vec<(&PackageRecord, f32)> = self
+            .packages
+            .iter()
+            .filter_map(|record| Some((record, pattern::fuzzy_score(query, &record.name)?)))
+            .collect();
+        matches.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        matches
     }
 
-    match collect_pip() {
-        Ok(packages) => summary.snapshot.packages.extend(packages),
-        Err(err) => summary.push_warning(PackageManager::Pip, err),
+    /// Returns packages whose `current_version` is older than in `baseline`, in this snapshot's
+    /// order. Packages are matched by `(manager, name)`, as in [`diff_snapshots`].
+    pub fn regressions(&self, baseline: &InventorySnapshot) -> Vec<VersionChange> {
+        diff_snapshots(baseline, self)
+            .changed
+            .into_iter()
+            .filter(|change| compare_versions(&change.to, &change.from) == std::cmp::Ordering::Less)
+            .collect()
     }
 
-    summary
-}
+    /// Returns records installed at or after `cutoff`, in snapshot order.
+    ///
+    /// Records with a missing or unparseable `installed_at` are left out.
+    pub fn installed_since(&self, cutoff: OffsetDateTime) -> Vec<&PackageRecord> {
+        self.packages
+            .iter()
+            .filter(|record| {
+                record
+                    .installed_at_time()
+                    .is_some_and(|installed_at| installed_at >= cutoff)
+            })
+            .collect()
+    }
 
-fn collect_brew() -> Result<Vec<PackageRecord>, CollectionError> {
-    let list_output = run_command("brew", &["list", "--versions"], None::<&[i32]>)?;
-    ensure_success(&list_output, "brew list --versions")?;
+    /// Counts available updates by severity; records without a newer known latest version are
+    /// not counted.
+    pub fn update_debt(&self) -> UpdateDebt {
+        let mut debt = UpdateDebt::default();
+        for kind in self.packages.iter().filter_map(PackageRecord::update_kind) {
+            debt.add(kind);
+        }
+        debt
+    }
 
-    let mut installed: HashMap<String, String> = HashMap::new();
-    for line in list_output
-        .stdout
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-    {
-        let mut parts = line.split_whitespace();
-        if let (Some(name), Some(version)) = (parts.next(), parts.next_back()) {
-            installed.insert(name.to_string(), version.to_string());
+    /// Groups records by the severity of their available update, in snapshot order.
+    ///
+    /// Records without a newer known latest version (current, unknown, or outdated without a
+    /// parseable latest version) are excluded, as in [`update_debt`](Self::update_debt), and
+    /// kinds with no records have no entry.
+    pub fn partition_by_update_kind(&self) -> HashMap<UpdateKind, Vec<&PackageRecord>> {
+        let mut partitions: HashMap<UpdateKind, Vec<&PackageRecord>> = HashMap::new();
+        for record in &self.packages {
+            if let Some(kind) = record.update_kind() {
+                partitions.entry(kind).or_default().push(record);
+            }
         }
+        partitions
     }
 
-    if installed.is_empty() {
-        return Ok(Vec::new());
+    /// Sums the known download sizes of outdated packages; records without a size count as zero.
+    pub fn estimated_download_bytes(&self) -> u64 {
+        self.packages
+            .iter()
+            .filter(|record| record.status == PackageStatus::Outdated)
+            .filter_map(|record| record.download_size)
+            .sum()
     }
 
-    let outdated_output = run_command("brew", &["outdated", "--json=v2"], None::<&[i32]>)?;
-    ensure_success(&outdated_output, "brew outdated --json=v2")?;
+    /// Returns a copy containing only outdated packages, preserving snapshot metadata.
+    pub fn only_outdated(&self) -> InventorySnapshot {
+        self.with_packages(
+            self.packages
+                .iter()
+                .filter(|record| record.status == PackageStatus::Outdated)
+                .cloned()
+                .collect(),
+        )
+    }
 
-    #[derive(Debug, Deserialize)]
-    struct BrewOutdated {
-        formulae: Vec<BrewFormula>,
+    /// Returns a copy without packages whose status is [`PackageStatus::Unknown`], preserving
+    /// snapshot metadata.
+    pub fn without_unknown(&self) -> InventorySnapshot {
+        self.with_packages(
+            self.packages
+                .iter()
+                .filter(|record| record.status != PackageStatus::Unknown)
+                .cloned()
+                .collect(),
+        )
     }
 
-    #[derive(Debug, Deserialize)]
-    struct BrewFormula {
-        name: String,
-        #[serde(default)]
-        #[allow(dead_code)]
-        installed_versions: Vec<String>,
-        #[serde(default)]
-        current_version: Option<String>,
-        #[serde(default)]
-        latest_version: Option<String>,
+    /// Returns up to `limit` packages starting at `offset`, with `total` set to the full
+    /// package count. Offsets past the end and a zero limit yield an empty page.
+    pub fn page(&self, offset: usize, limit: usize) -> InventorySnapshot {
+        let packages = self
+            .packages
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect();
+
+        InventorySnapshot {
+            total: Some(self.packages.len()),
+            ..self.with_packages(packages)
+        }
     }
 
-    let mut latest_map: HashMap<String, String> = HashMap::new();
-    if !outdated_output.stdout.trim().is_empty() {
-        let parsed: BrewOutdated = serde_json::from_str(&outdated_output.stdout)?;
-        for formula in parsed.formulae {
-            if let Some(latest) = formula
-                .latest_version
-                .or(formula.current_version)
-                .filter(|v| !v.is_empty())
-            {
-                latest_map.insert(formula.name, latest);
-            }
+    /// Copies the snapshot metadata around a different package list.
+    fn with_packages(&self, packages: Vec<PackageRecord>) -> InventorySnapshot {
+        InventorySnapshot {
+            generated_at: self.generated_at.clone(),
+            packages,
+            total: None,
         }
     }
 
-    let packages = installed
-        .into_iter()
-        .map(|(name, current_version)| {
-            let latest_version = latest_map.get(&name).cloned();
-            let status = if let Some(latest) = &latest_version {
-                if latest != &current_version {
-                    PackageStatus::Outdated
-                } else {
-                    PackageStatus::Current
-                }
-            } else {
-                PackageStatus::Current
-            };
+    /// Sets the generation timestamp using an ISO-8601 string.
+    pub fn set_generated_at(&mut self, iso_timestamp: impl Into<String>) {
+        self.generated_at = Some(iso_timestamp.into());
+    }
 
-            PackageRecord {
-                name,
-                current_version,
-                latest_version,
-                installed_at: None,
-                status,
-                manager: PackageManager::Brew,
-            }
-        })
-        .collect();
+    /// Returns how long before `now` the snapshot was generated, e.g. to show "scanned 2 hours
+    /// ago".
+    ///
+    /// `None` when `generated_at` is missing or not RFC 3339; a timestamp after `now` (clock
+    /// skew between machines) counts as zero.
+    pub fn age(&self, now: OffsetDateTime) -> Option<Duration> {
+        let generated_at = OffsetDateTime::parse(self.generated_at.as_deref()?, &Rfc3339).ok()?;
+        Some(Duration::try_from(now - generated_at).unwrap_or(Duration::ZERO))
+    }
 
-    Ok(packages)
-}
+    /// Hashes the package records, ignoring `generated_at`, so repeated scans can be compared
+    /// cheaply. Record order matters; the value is only stable within one build of bagpack.
+    pub fn content_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
 
-fn collect_npm() -> Result<Vec<PackageRecord>, CollectionError> {
-    let list_output = run_command("npm", &["ls", "-g", "--depth=0", "--json"], None::<&[i32]>)?;
-    ensure_success(&list_output, "npm ls -g --depth=0 --json")?;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.packages.hash(&mut hasher);
+        hasher.finish()
+    }
 
-    #[derive(Debug, Deserialize)]
-    struct NpmTree {
-        #[serde(default)]
-        dependencies: HashMap<String, NpmPackage>,
+    /// Returns each manager with at least one record, ordered like [`PackageManager::ALL`] with
+    /// custom managers last by name.
+    pub fn managers(&self) -> Vec<PackageManager> {
+        self.packages
+            .iter()
+            .map(|record| record.manager.clone())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
     }
 
-    #[derive(Debug, Deserialize)]
-    struct NpmPackage {
-        #[serde(default)]
-        version: Option<String>,
+    /// Buckets packages by manager, ordered by manager and preserving snapshot order within each.
+    pub fn group_by_manager(&self) -> BTreeMap<PackageManager, Vec<&PackageRecord>> {
+        let mut groups: BTreeMap<PackageManager, Vec<&PackageRecord>> = BTreeMap::new();
+        for record in &self.packages {
+            groups
+                .entry(record.manager.clone())
+                .or_default()
+                .push(record);
+        }
+        groups
     }
 
-    let tree: NpmTree = serde_json::from_str(&list_output.stdout)?;
+    /// Lists names that appear more than once within the same manager, with the distinct
+    /// versions seen in snapshot order.
+    pub fn duplicates(&self) -> Vec<(PackageManager, String, Vec<String>)> {
+        struct Group<'a> {
+            manager: PackageManager,
+            name: &'a str,
+            occurrences: usize,
+            versions: Vec<&'a str>,
+        }
 
-    let outdated_output = run_command("npm", &["outdated", "-g", "--json"], Some(&[0, 1]))?;
-    // npm returns exit code 1 when outdated packages exist; treat 0/1 as success.
-    let mut outdated_map: HashMap<String, String> = HashMap::new();
-    if !outdated_output.stdout.trim().is_empty() {
-        let value: serde_json::Value = serde_json::from_str(&outdated_output.stdout)?;
-        if let serde_json::Value::Object(entries) = value {
-            for (name, details) in entries {
-                if let Some(latest) = details.get("latest").and_then(|v| v.as_str()) {
-                    outdated_map.insert(name, latest.to_string());
+        let mut groups: Vec<Group> = Vec::new();
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+
+        for record in &self.packages {
+            let slots = by_name.entry(record.name.as_str()).or_default();
+            let existing = slots
+                .iter()
+                .copied()
+                .find(|&slot| groups[slot].manager == record.manager);
+
+            match existing {
+                Some(slot) => {
+                    let group = &mut groups[slot];
+                    group.occurrences += 1;
+                    if !group.versions.contains(&record.current_version.as_str()) {
+                        group.versions.push(&record.current_version);
+                    }
+                }
+                None => {
+                    slots.push(groups.len());
+                    groups.push(Group {
+                        manager: record.manager.clone(),
+                        name: &record.name,
+                        occurrences: 1,
+                        versions: vec![&record.current_version],
+                    });
                 }
             }
         }
-    }
 
-    let packages = tree
-        .dependencies
-        .into_iter()
-        .filter_map(|(name, pkg)| {
-            pkg.version.map(|current_version| {
-                let latest_version = outdated_map.get(&name).cloned();
-                let status = if latest_version.is_some() {
-                    PackageStatus::Outdated
-                } else {
-                    PackageStatus::Current
-                };
+        groups
+            .into_iter()
+            .filter(|group| group.occurrences > 1)
+            .map(|group| {
+                (
+                    group.manager,
+                    group.name.to_string(),
+                    group.versions.into_iter().map(str::to_string).collect(),
+                )
+            })
+            .collect()
+    }
 
-                PackageRecord {
-                    name,
-                    current_version,
-                    latest_version,
-                    installed_at: None,
-                    status,
-                    manager: PackageManager::Npm,
-                }
+    /// Drops records matching any `(manager, pattern)` rule; a `None` manager matches records
+    /// from every manager.
+    ///
+    /// Patterns must match the whole name, so `pip` does not hide `pipx` but `py*` does.
+    pub fn remove_ignored(&mut self, rules: &[(Option<PackageManager>, String)]) {
+        self.packages.retain(|record| {
+            !rules.iter().any(|(manager, pattern)| {
+                manager
+                    .as_ref()
+                    .is_none_or(|manager| *manager == record.manager)
+                    && pattern::matches_whole(pattern, &record.name)
             })
-        })
-        .collect();
+        });
+    }
 
-    Ok(packages)
+    /// Replaces the names of packages matching any of `patterns` with [`REDACTED_NAME`].
+    ///
+    /// Patterns with `*`/`?` wildcards match whole names; plain patterns match as a prefix.
+    pub fn redact(&mut self, patterns: &[String]) {
+        for record in &mut self.packages {
+            if patterns
+                .iter()
+                .any(|pattern| pattern::matches(pattern, &record.name))
+            {
+                record.name = REDACTED_NAME.to_string();
+            }
+        }
+    }
 }
 
-fn collect_pip() -> Result<Vec<PackageRecord>, CollectionError> {
-    let list_output = run_command("pip", &["list", "--format=json"], None::<&[i32]>)?;
-    ensure_success(&list_output, "pip list --format=json")?;
+impl CollectionSummary {
+    pub fn new(snapshot: InventorySnapshot) -> Self {
+        Self {
+            snapshot,
+            warnings: Vec::new(),
+            attempted_managers: Vec::new(),
+            raw_outputs: HashMap::new(),
+            durations: HashMap::new(),
+            cancelled: false,
+        }
+    }
 
-    #[derive(Debug, Deserialize)]
-    struct PipPackage {
-        name: String,
-        version: String,
+    /// Returns whether every attempted manager failed, as opposed to nothing being installed.
+    pub fn is_total_failure(&self) -> bool {
+        !self.warnings.is_empty() && self.snapshot.is_empty()
     }
 
-    let installed: Vec<PipPackage> = serde_json::from_str(&list_output.stdout)?;
+    /// Attempted managers that finished without a warning, in execution order.
+    pub fn succeeded_managers(&self) -> Vec<PackageManager> {
+        self.attempted_managers
+            .iter()
+            .filter(|manager| {
+                !self
+                    .warnings
+                    .iter()
+                    .any(|warning| &warning.manager == *manager)
+            })
+            .cloned()
+            .collect()
+    }
 
-    #[derive(Debug, Deserialize)]
-    struct PipOutdated {
-        name: String,
-        #[serde(rename = "latest_version")]
-        latest_version: String,
+    /// Serializes only the warnings, for front ends polling for new errors.
+    pub fn warnings_json(&self) -> String {
+        serde_json::to_string(&self.warnings).expect("warnings serialize to JSON")
     }
 
-    let outdated_output = run_command(
-        "pip",
-        &["list", "--outdated", "--format=json"],
-        None::<&[i32]>,
-    )?;
-    ensure_success(&outdated_output, "pip list --outdated --format=json")?;
+    /// Groups warnings by the manager that reported them, preserving their order.
+    pub fn warnings_by_manager(&self) -> HashMap<PackageManager, Vec<&CollectionWarning>> {
+        let mut groups: HashMap<PackageManager, Vec<&CollectionWarning>> = HashMap::new();
+        for warning in &self.warnings {
+            groups
+                .entry(warning.manager.clone())
+                .or_default()
+                .push(warning);
+        }
+        groups
+    }
 
-    let mut outdated_map: HashMap<String, String> = HashMap::new();
-    if !outdated_output.stdout.trim().is_empty() {
-        let outdated: Vec<PipOutdated> = serde_json::from_str(&outdated_output.stdout)?;
-        for pkg in outdated {
-            outdated_map.insert(pkg.name, pkg.latest_version);
+    /// Collapses warnings with the same manager and message into the first of them, adding up
+    /// their counts, e.g. when watch mode hits the same failure on every refresh.
+    pub fn dedupe_warnings(&mut self) {
+        let mut unique: Vec<CollectionWarning> = Vec::with_capacity(self.warnings.len());
+        for warning in self.warnings.drain(..) {
+            match unique
+                .iter_mut()
+                .find(|seen| seen.manager == warning.manager && seen.message == warning.message)
+            {
+                Some(seen) => seen.count += warning.count,
+                None => unique.push(warning),
+            }
         }
+        self.warnings = unique;
     }
 
-    let packages = installed
+    fn push_warning(&mut self, manager: PackageManager, error: CollectionError) {
+        self.warnings.push(CollectionWarning {
+            manager,
+            message: error.to_string(),
+            count: 1,
+        });
+    }
+}
+
+/// Options accepted by [`collect_inventory_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectInventoryOptions {
+    /// Managers to collect from, in order. Packages appear in the snapshot in the same order;
+    /// repeated entries are collected once. Managers outside the default set (such as rustup)
+    /// are opt-in.
+    pub managers: Vec<PackageManager>,
+    /// Package names to hide before the snapshot is returned; see [`InventorySnapshot::redact`].
+    pub redact_patterns: Vec<String>,
+    /// `(manager, pattern)` rules for records to drop after collection, such as
+    /// `(Some(PackageManager::Pip), "setuptools")`; see [`InventorySnapshot::remove_ignored`].
+    pub ignore: Vec<(Option<PackageManager>, String)>,
+    /// Extra environment variables for every spawned command, e.g. `HOMEBREW_NO_AUTO_UPDATE=1`.
+    ///
+    /// These are added on top of the inherited environment rather than replacing it.
+    pub env: HashMap<String, String>,
+    /// Upper bound on collectors running at the same time; `1` collects sequentially and `0` is
+    /// treated as `1`.
+    pub max_concurrency: usize,
+    /// Non-zero exit codes to accept as success, keyed by the full command line bagpack runs
+    /// (e.g. `"npm outdated -g --json"`).
+    ///
+    /// An entry replaces the command's built-in allowance (npm's `outdated` already accepts `1`),
+    /// which helps when a newer tool release changes its exit code conventions.
+    pub allowed_exit_codes: HashMap<String, Vec<i32>>,
+    /// opam switch to collect from; `None` uses the current switch. The switch name becomes the
+    /// record `source`.
+    pub opam_switch: Option<String>,
+    /// Keep the raw stdout of each command in [`CollectionSummary::raw_outputs`], for attaching
+    /// to bug reports when a parser fails.
+    pub capture_raw: bool,
+    /// Mix project whose Hex dependencies are checked with `mix hex.outdated`; without one only
+    /// globally installed archives are collected.
+    pub hex_project: Option<PathBuf>,
+    /// Look up each package's homepage with an extra command where the manager supports it
+    /// (`brew info --json=v2 --installed`).
+    pub homepages: bool,
+    /// Decode command output with [`String::from_utf8_lossy`], replacing invalid sequences with
+    /// `U+FFFD`, instead of failing the collector on non-UTF-8 bytes.
+    pub lossy_utf8: bool,
+    /// Keep at most this many records per manager, the alphabetically first by name, recording a
+    /// warning for each manager that was cut short.
+    pub max_per_manager: Option<usize>,
+    /// Depth passed to `npm ls -g`; above `0`, packages nested under global tools are collected
+    /// as dependencies, once per name.
+    pub npm_depth: u32,
+    /// Check each npm package for an upstream deprecation notice with `npm view`, at the cost of
+    /// one registry request per package.
+    pub deprecations: bool,
+    /// Directory that manager commands run from unless a collector picks its own (such as
+    /// [`hex_project`](Self::hex_project)); it matters only to project-scoped managers, as
+    /// global inventories do not depend on the directory.
+    pub working_dir: Option<PathBuf>,
+    /// Record the commands each built-in collector ran in [`PackageRecord::provenance`], for
+    /// tracking down parser mismatches.
+    pub record_provenance: bool,
+    /// Look up the bottle download size of each outdated Homebrew formula with
+    /// `brew info --json=v2`, for [`InventorySnapshot::estimated_download_bytes`].
+    pub download_sizes: bool,
+    /// Token to abort the collection with; see [`CancellationToken`].
+    pub cancel: CancellationToken,
+    /// How many times to re-run a command that exits with a disallowed status before giving
+    /// up, for flaky managers (e.g. a registry timeout in `npm outdated`). Only the final
+    /// failure becomes a warning, and it says how many attempts were made; `0` disables retries.
+    pub command_retries: u32,
+    /// Only count security updates as outdated where the manager can tell them apart
+    /// (`dnf check-update --security`); other updates then leave a record `current`. Other
+    /// managers ignore it.
+    pub security_only: bool,
+}
+
+impl Default for CollectInventoryOptions {
+    /// Selects Homebrew, npm, and pip everywhere, plus Flatpak and Snap on Linux.
+    fn default() -> Self {
+        let mut managers = vec![
+            PackageManager::Brew,
+            PackageManager::Npm,
+            PackageManager::Pip,
+        ];
+        if cfg!(target_os = "linux") {
+            managers.extend([PackageManager::Flatpak, PackageManager::Snap]);
+        }
+
+        Self {
+            managers,
+            redact_patterns: Vec::new(),
+            ignore: Vec::new(),
+            env: HashMap::new(),
+            max_concurrency: 4,
+            allowed_exit_codes: HashMap::new(),
+            opam_switch: None,
+            capture_raw: false,
+            hex_project: None,
+            homepages: false,
+            lossy_utf8: false,
+            max_per_manager: None,
+            npm_depth: 0,
+            deprecations: false,
+            working_dir: None,
+            record_provenance: false,
+            download_sizes: false,
+            cancel: CancellationToken::new(),
+            command_retries: 0,
+            security_only: false,
+        }
+    }
+}
+
+/// Gather package inventories from the default set of managers.
+///
+/// The function attempts each manager independently and records failures as warnings so that
+/// remaining data can still surface to the UI.
+pub fn collect_inventory() -> CollectionSummary {
+    collect_inventory_with(&CollectInventoryOptions::default())
+}
+
+/// Gather package inventories from the managers selected in `options`.
+pub fn collect_inventory_with(options: &CollectInventoryOptions) -> CollectionSummary {
+    collect_inventory_with_collectors(options, &[])
+}
+
+/// Like [`collect_inventory_with`], but fails with every warning if any manager did, for scripts
+/// that should not act on a partial inventory.
+pub fn collect_inventory_strict(
+    options: &CollectInventoryOptions,
+) -> Result<InventorySnapshot, Vec<CollectionWarning>> {
+    let summary = collect_inventory_with(options);
+    if summary.warnings.is_empty() {
+        Ok(summary.snapshot)
+    } else {
+        Err(summary.warnings)
+    }
+}
+
+/// Source of records for a package manager bagpack has no built-in collector for.
+///
+/// Collectors passed to [`collect_inventory_with_collectors`] run on the same worker threads as
+/// the built-in ones, so they must be `Send + Sync`.
+pub trait Collector: Send + Sync {
+    /// The manager the collected records belong to, usually [`PackageManager::Other`].
+    fn manager(&self) -> PackageManager;
+
+    /// Collects the manager's records; an error becomes a warning like any built-in failure.
+    fn collect(&self) -> Result<Vec<PackageRecord>, CollectionError>;
+}
+
+/// Like [`collect_inventory_with`], but also runs `collectors` after the built-in managers.
+///
+/// Their records follow the built-in ones in slice order, and their managers are appended to
+/// [`CollectionSummary::attempted_managers`].
+pub fn collect_inventory_with_collectors(
+    options: &CollectInventoryOptions,
+    collectors: &[Box<dyn Collector>],
+) -> CollectionSummary {
+    let mut snapshot = InventorySnapshot::default();
+
+    if let Ok(timestamp) = OffsetDateTime::now_utc().format(&Rfc3339) {
+        snapshot.set_generated_at(timestamp);
+    }
+
+    let mut summary = CollectionSummary::new(snapshot);
+
+    let built_in = unique_managers(&options.managers);
+    let sources: Vec<(PackageManager, Option<&dyn Collector>)> = built_in
         .into_iter()
-        .map(|pkg| {
-            let latest_version = outdated_map.get(&pkg.name).cloned();
-            let status = if latest_version.is_some() {
-                PackageStatus::Outdated
-            } else {
-                PackageStatus::Current
-            };
+        .map(|manager| (manager, None))
+        .chain(
+            collectors
+                .iter()
+                .map(|collector| (collector.manager(), Some(collector.as_ref()))),
+        )
+        .collect();
 
-            PackageRecord {
-                name: pkg.name,
-                current_version: pkg.version,
-                latest_version,
-                installed_at: None,
-                status,
-                manager: PackageManager::Pip,
+    let jobs = sources
+        .iter()
+        .map(|(manager, collector)| {
+            move || {
+                if options.cancel.is_cancelled() {
+                    return (Err(CollectionError::Cancelled), Vec::new(), Duration::ZERO);
+                }
+                let started = Instant::now();
+                let (result, raw) =
+                    capturing_raw_outputs(options.capture_raw, || match collector {
+                        Some(collector) => collector.collect(),
+                        None => collect_manager(manager, options),
+                    });
+                (result, raw, started.elapsed())
             }
         })
         .collect();
+    let results = run_bounded(options.max_concurrency, jobs);
 
-    Ok(packages)
+    // Results come back in job order, so the snapshot follows `options.managers`.
+    let managers: Vec<PackageManager> = sources.into_iter().map(|(manager, _)| manager).collect();
+    for (manager, (result, raw, elapsed)) in managers.into_iter().zip(results) {
+        if matches!(result, Err(CollectionError::Cancelled)) {
+            log::info!("{}: collection cancelled", manager.as_str());
+            summary.cancelled = true;
+            continue;
+        }
+        summary.attempted_managers.push(manager.clone());
+        summary.durations.insert(manager.clone(), elapsed);
+        if options.capture_raw {
+            summary.raw_outputs.insert(manager.clone(), raw);
+        }
+        match result {
+            Ok(mut packages) => {
+                log::info!(
+                    "{}: collected {} packages",
+                    manager.as_str(),
+                    packages.len()
+                );
+                if let Some(warning) = cap_packages(options, &manager, &mut packages) {
+                    summary.warnings.push(warning);
+                }
+                summary.snapshot.packages.extend(packages);
+            }
+            Err(err) => {
+                log::warn!("{}: collection failed: {err}", manager.as_str());
+                summary.push_warning(manager, err);
+            }
+        }
+    }
+
+    summary.snapshot.remove_ignored(&options.ignore);
+    // Redact here rather than in the exporters so no output format can leak the names.
+    summary.snapshot.redact(&options.redact_patterns);
+
+    summary
 }
 
-fn ensure_success(output: &CommandResult, label: &str) -> Result<(), CollectionError> {
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(CollectionError::Command(CommandError::Status {
-            program: label.to_string(),
-            code: output.status.code(),
-            stderr: output.stderr.clone(),
-        }))
+/// Re-collects only the managers in `options.managers`, carrying every other manager's records
+/// over from `previous` unchanged.
+///
+/// Carried-over records keep their order ahead of the refreshed ones. A refreshed manager that
+/// fails keeps its previous records alongside the warning, so one flaky CLI does not blank its
+/// tab. `generated_at` reflects this collection.
+pub fn collect_incremental(
+    previous: &InventorySnapshot,
+    options: &CollectInventoryOptions,
+) -> CollectionSummary {
+    let mut summary = collect_inventory_with(options);
+
+    // A manager truncated by `max_per_manager` has a warning but still returned fresh records.
+    let mut refreshed = summary.succeeded_managers();
+    for record in &summary.snapshot.packages {
+        if !refreshed.contains(&record.manager) {
+            refreshed.push(record.manager.clone());
+        }
     }
+    let mut packages: Vec<PackageRecord> = previous
+        .packages
+        .iter()
+        .filter(|record| !refreshed.contains(&record.manager))
+        .cloned()
+        .collect();
+    packages.append(&mut summary.snapshot.packages);
+    summary.snapshot.packages = packages;
+
+    summary.snapshot.remove_ignored(&options.ignore);
+    summary.snapshot.redact(&options.redact_patterns);
+
+    summary
 }
 
-fn run_command(
-    program: &str,
-    args: &[&str],
-    allowed_exit_codes: Option<&[i32]>,
-) -> Result<CommandResult, CollectionError> {
-    let output = Command::new(program)
-        .args(args)
-        .output()
-        .map_err(|source| {
-            CollectionError::Command(CommandError::Spawn {
-                program: program.to_string(),
-                source,
-            })
-        })?;
+/// Like [`collect_inventory_with`], but hands records to `sink` as each manager finishes instead
+/// of building a snapshot, returning the warnings once every manager has run.
+///
+/// Records arrive grouped by manager in completion order rather than `options.managers` order.
+/// Ignore rules and redaction still apply; raw output capture and durations are not recorded.
+/// Managers skipped or interrupted by [`CollectInventoryOptions::cancel`] produce no warning.
+pub fn collect_streaming(
+    options: &CollectInventoryOptions,
+    mut sink: impl FnMut(PackageRecord),
+) -> Vec<CollectionWarning> {
+    let managers = unique_managers(&options.managers);
 
-    let stdout = String::from_utf8(output.stdout).map_err(|source| {
-        CollectionError::Command(CommandError::Utf8 {
-            program: program.to_string(),
-            source,
+    let (sender, receiver) = mpsc::channel();
+    let jobs: Vec<_> = managers
+        .into_iter()
+        .map(|manager| {
+            let sender = sender.clone();
+            move || {
+                let result = if options.cancel.is_cancelled() {
+                    Err(CollectionError::Cancelled)
+                } else {
+                    collect_manager(&manager, options)
+                };
+                // The receiver only goes away once every job has finished.
+                let _ = sender.send((manager, result));
+            }
         })
-    })?;
+        .collect();
+    drop(sender);
 
-    let stderr = String::from_utf8(output.stderr).map_err(|source| {
-        CollectionError::Command(CommandError::Utf8 {
-            program: program.to_string(),
-            source,
-        })
-    })?;
+    let mut warnings = Vec::new();
+    thread::scope(|scope| {
+        scope.spawn(|| run_bounded(options.max_concurrency, jobs));
 
-    if !output.status.success() {
-        if let Some(codes) = allowed_exit_codes {
-            if let Some(code) = output.status.code() {
-                if codes.contains(&code) {
-                    return Ok(CommandResult {
-                        stdout,
-                        stderr,
-                        status: output.status,
+        for (manager, result) in receiver {
+            match result {
+                Ok(mut packages) => {
+                    log::info!(
+                        "{}: collected {} packages",
+                        manager.as_str(),
+                        packages.len()
+                    );
+                    warnings.extend(cap_packages(options, &manager, &mut packages));
+                    let mut batch = InventorySnapshot {
+                        packages,
+                        ..InventorySnapshot::default()
+                    };
+                    batch.remove_ignored(&options.ignore);
+                    batch.redact(&options.redact_patterns);
+                    batch.packages.into_iter().for_each(&mut sink);
+                }
+                Err(CollectionError::Cancelled) => {
+                    log::info!("{}: collection cancelled", manager.as_str());
+                }
+                Err(err) => {
+                    log::warn!("{}: collection failed: {err}", manager.as_str());
+                    warnings.push(CollectionWarning {
+                        manager,
+                        message: err.to_string(),
+                        count: 1,
                     });
                 }
             }
         }
+    });
 
-        return Err(CollectionError::Command(CommandError::Status {
-            program: format!("{} {}", program, args.join(" ")),
-            code: output.status.code(),
-            stderr,
-        }));
+    warnings
+}
+
+/// Applies [`CollectInventoryOptions::max_per_manager`] to one manager's records, sorting them by
+/// name first, and returns a warning if any were dropped.
+fn cap_packages(
+    options: &CollectInventoryOptions,
+    manager: &PackageManager,
+    packages: &mut Vec<PackageRecord>,
+) -> Option<CollectionWarning> {
+    let max = options.max_per_manager?;
+    let total = packages.len();
+    if total <= max {
+        return None;
     }
 
-    Ok(CommandResult {
-        stdout,
-        stderr,
-        status: output.status,
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages.truncate(max);
+    log::warn!(
+        "{}: truncated to {max} of {total} packages",
+        manager.as_str()
+    );
+    Some(CollectionWarning {
+        manager: manager.clone(),
+        message: format!("showing {max} of {total} packages (max_per_manager)"),
+        count: 1,
     })
 }
 
-#[derive(Debug)]
-struct CommandResult {
-    stdout: String,
-    stderr: String,
-    status: ExitStatus,
+/// `managers` without repeated entries, keeping the first occurrence of each.
+fn unique_managers(managers: &[PackageManager]) -> Vec<PackageManager> {
+    let mut unique: Vec<PackageManager> = Vec::with_capacity(managers.len());
+    for manager in managers {
+        if !unique.contains(manager) {
+            unique.push(manager.clone());
+        }
+    }
+    unique
 }
 
-#[derive(Debug, Error)]
-pub enum CollectionError {
-    #[error(transparent)]
-    Command(#[from] CommandError),
-    #[error("json parse error: {0}")]
-    Json(#[from] serde_json::Error),
+thread_local! {
+    /// Stdout captured by [`run_command`] for the collector running on this thread, if enabled.
+    static RAW_OUTPUTS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+
+    /// Command lines run by [`run_command`] for the collector on this thread, when recording
+    /// provenance.
+    static RAN_COMMANDS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
 }
 
-#[derive(Debug, Error)]
-pub enum CommandError {
-    #[error("failed to spawn {program}: {source}")]
-    Spawn {
-        program: String,
-        #[source]
-        source: std::io::Error,
-    },
-    #[error("{program} exited with status {code:?}: {stderr}")]
-    Status {
-        program: String,
-        code: Option<i32>,
-        stderr: String,
-    },
-    #[error("{program} produced invalid UTF-8: {source}")]
-    Utf8 {
-        program: String,
-        #[source]
-        source: std::string::FromUtf8Error,
-    },
+/// Runs `collect`, returning the stdout of every command it ran when `capture` is set.
+///
+/// Each collector runs on its own thread, so a thread-local buffer keeps managers apart
+/// without threading a sink through every collector.
+fn capturing_raw_outputs<T>(capture: bool, collect: impl FnOnce() -> T) -> (T, Vec<String>) {
+    if !capture {
+        return (collect(), Vec::new());
+    }
+
+    RAW_OUTPUTS.with(|raw| *raw.borrow_mut() = Some(Vec::new()));
+    let result = collect();
+    let raw = RAW_OUTPUTS.with(|raw| raw.borrow_mut().take().unwrap_or_default());
+    (result, raw)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{InventorySnapshot, PackageManager, PackageRecord, PackageStatus};
+/// Runs `jobs` on scoped threads with at most `limit` (minimum one) running at once, returning
+/// their results in job order.
+fn run_bounded<T, F>(limit: usize, jobs: Vec<F>) -> Vec<T>
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    let limit = limit.max(1);
+    let running = Mutex::new(0usize);
+    let slot_freed = Condvar::new();
 
-    #[test]
-    fn counts_outdated_packages() {
-        let mut snapshot = InventorySnapshot::default();
-        snapshot.push(PackageRecord {
-            name: "wget".into(),
-            current_version: "1.24.5".into(),
-            latest_version: Some("1.24.6".into()),
-            installed_at: None,
-            status: PackageStatus::Outdated,
-            manager: PackageManager::Brew,
-        });
-        snapshot.push(PackageRecord {
-            name: "typescript".into(),
-            current_version: "5.5.2".into(),
-            latest_version: Some("5.6.3".into()),
-            installed_at: None,
-            status: PackageStatus::Current,
-            manager: PackageManager::Npm,
-        });
-        snapshot.set_generated_at("2025-10-05T00:00:00Z");
+    thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .into_iter()
+            .map(|job| {
+                let mut active = running.lock().unwrap();
+                while *active >= limit {
+                    active = slot_freed.wait(active).unwrap();
+                }
+                *active += 1;
+                drop(active);
 
-        assert_eq!(snapshot.outdated_count(), 1);
-        assert_eq!(
-            snapshot.generated_at.as_deref(),
-            Some("2025-10-05T00:00:00Z")
+                let (running, slot_freed) = (&running, &slot_freed);
+                scope.spawn(move || {
+                    let result = job();
+                    *running.lock().unwrap() -= 1;
+                    slot_freed.notify_one();
+                    result
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("collector thread panicked"))
+            .collect()
+    })
+}
+
+fn collect_manager(
+    manager: &PackageManager,
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    if !options.record_provenance {
+        let mut packages = run_collector(manager, options)?;
+        mark_ahead(&mut packages);
+        return Ok(packages);
+    }
+
+    RAN_COMMANDS.with(|ran| *ran.borrow_mut() = Some(Vec::new()));
+    let result = run_collector(manager, options);
+    let commands = RAN_COMMANDS.with(|ran| ran.borrow_mut().take().unwrap_or_default());
+
+    let mut packages = result?;
+    mark_ahead(&mut packages);
+    if !commands.is_empty() {
+        let provenance = commands.join("; ");
+        for record in &mut packages {
+            record.provenance = Some(provenance.clone());
+        }
+    }
+    Ok(packages)
+}
+
+/// Turns outdated records whose reported latest version is older than the installed one (by
+/// [`compare_versions`]) into [`PackageStatus::Ahead`]; such a "latest" is no upgrade.
+fn mark_ahead(packages: &mut [PackageRecord]) {
+    for record in packages {
+        let ahead = record.status == PackageStatus::Outdated
+            && record.latest_version.as_deref().is_some_and(|latest| {
+                compare_versions(latest, &record.current_version) == std::cmp::Ordering::Less
+            });
+        if ahead {
+            record.status = PackageStatus::Ahead;
+        }
+    }
+}
+
+fn run_collector(
+    manager: &PackageManager,
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    match manager {
+        PackageManager::Brew => collect_brew(options),
+        PackageManager::Npm => collect_npm(options),
+        PackageManager::Pip => collect_pip(options),
+        PackageManager::Flatpak => flatpak::collect_flatpak(options),
+        PackageManager::Snap => snap::collect_snap(&with_default_env(options, C_LOCALE_ENV)),
+        PackageManager::Rustup => rustup::collect_rustup(options),
+        PackageManager::Mas => mas::collect_mas(options),
+        PackageManager::Composer => composer::collect_composer(options),
+        PackageManager::DotnetTool => {
+            dotnet_tool::collect_dotnet_tool(&with_default_env(options, C_LOCALE_ENV))
+        }
+        PackageManager::Nix => nix::collect_nix(options),
+        PackageManager::Pacman => pacman::collect_pacman(options),
+        #[cfg(feature = "zypper")]
+        PackageManager::Zypper => zypper::collect_zypper(options),
+        #[cfg(not(feature = "zypper"))]
+        PackageManager::Zypper => Err(CollectionError::FeatureDisabled {
+            manager: manager.clone(),
+            feature: "zypper",
+        }),
+        PackageManager::Asdf => asdf::collect_asdf(options),
+        PackageManager::MacPorts => macports::collect_macports(options),
+        PackageManager::Opam => opam::collect_opam(options),
+        PackageManager::Choco => choco::collect_choco(options),
+        PackageManager::Gem => gem::collect_gem(options),
+        PackageManager::Cabal => cabal::collect_cabal(options),
+        PackageManager::Hex => hex::collect_hex(options),
+        PackageManager::Apk => apk::collect_apk(options),
+        PackageManager::Dnf => dnf::collect_dnf(&with_default_env(options, C_LOCALE_ENV)),
+        PackageManager::LuaRocks => luarocks::collect_luarocks(options),
+        PackageManager::Vcpkg => vcpkg::collect_vcpkg(options),
+        PackageManager::Pnpm => pnpm::collect_pnpm(options),
+        PackageManager::Other(_) => Err(CollectionError::NoCollector(manager.clone())),
+    }
+}
+
+/// Environment `brew` collection runs with unless `options.env` sets the variable itself (an
+/// empty value turns the behavior back on).
+///
+/// Skipping the auto-update keeps `brew outdated` from fetching and rebuilding the index on
+/// every scan, and collection should not report analytics either.
+const BREW_DEFAULT_ENV: &[(&str, &str)] = &[
+    ("HOMEBREW_NO_AUTO_UPDATE", "1"),
+    ("HOMEBREW_NO_ANALYTICS", "1"),
+];
+
+/// Environment for collectors that parse human-formatted output (column headers, section
+/// titles, status sentences), so a localized system does not break their parsers. As with
+/// [`BREW_DEFAULT_ENV`], `options.env` can override either variable.
+const C_LOCALE_ENV: &[(&str, &str)] = &[("LANG", "C"), ("LC_ALL", "C")];
+
+/// Returns a copy of `options` whose `env` also has `defaults`, without replacing variables the
+/// caller already set.
+fn with_default_env(
+    options: &CollectInventoryOptions,
+    defaults: &[(&str, &str)],
+) -> CollectInventoryOptions {
+    let mut options = options.clone();
+    for (key, value) in defaults {
+        options
+            .env
+            .entry(key.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+    options
+}
+
+fn collect_brew(options: &CollectInventoryOptions) -> Result<Vec<PackageRecord>, CollectionError> {
+    let options = &with_default_env(options, BREW_DEFAULT_ENV);
+
+    let list_output = run_command(options, "brew", &["list", "--versions"], None::<&[i32]>)?;
+    ensure_success(&list_output, "brew list --versions")?;
+
+    let mut installed: Vec<(String, String)> = Vec::new();
+    for line in list_output
+        .stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+    {
+        // Several versions of a formula can be installed side by side; report the newest.
+        let mut parts = line.split_whitespace();
+        let name = parts.next();
+        let newest = parts.max_by(|a, b| compare_versions(a, b));
+        if let (Some(name), Some(version)) = (name, newest) {
+            installed.push((name.to_string(), version.to_string()));
+        }
+    }
+
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Without the leaves every formula is reported as a top-level install rather than failing
+    // the whole collection over the `dependency` flag.
+    let leaves = match brew_leaves(options) {
+        Ok(leaves) => Some(leaves),
+        Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+        Err(err) => {
+            log::warn!("brew: leaves lookup failed: {err}");
+            None
+        }
+    };
+
+    let outdated_output = run_command(options, "brew", &["outdated", "--json=v2"], None::<&[i32]>)?;
+    ensure_success(&outdated_output, "brew outdated --json=v2")?;
+
+    #[derive(Debug, Deserialize)]
+    struct BrewOutdated {
+        formulae: Vec<BrewFormula>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewFormula {
+        name: String,
+        #[serde(default)]
+        #[allow(dead_code)]
+        installed_versions: Vec<String>,
+        #[serde(default)]
+        current_version: Option<String>,
+        #[serde(default)]
+        latest_version: Option<String>,
+    }
+
+    let mut latest_map: HashMap<String, String> = HashMap::new();
+    if !outdated_output.stdout.trim().is_empty() {
+        let parsed: BrewOutdated = serde_json::from_str(&outdated_output.stdout)?;
+        for formula in parsed.formulae {
+            if let Some(latest) = formula
+                .latest_version
+                .or(formula.current_version)
+                .filter(|v| !v.is_empty())
+            {
+                latest_map.insert(formula.name, latest);
+            }
+        }
+    }
+
+    let mut packages: Vec<PackageRecord> = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let dependency = leaves
+                .as_ref()
+                .is_some_and(|leaves| !leaves.contains(name.as_str()));
+            let latest_version = latest_map.get(&name).cloned();
+            let status = if let Some(latest) = &latest_version {
+                if normalize_version(latest) != normalize_version(&current_version) {
+                    PackageStatus::Outdated
+                } else {
+                    PackageStatus::Current
+                }
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Brew,
+                pinned: false,
+                dependency,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    if options.homepages {
+        // Homepages are cosmetic, so a failed lookup keeps the versions collected above.
+        match brew_homepages(options) {
+            Ok(homepages) => {
+                for record in &mut packages {
+                    record.homepage = homepages.get(&record.name).cloned();
+                }
+            }
+            Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+            Err(err) => log::warn!("brew: homepage lookup failed: {err}"),
+        }
+    }
+
+    if options.download_sizes {
+        let outdated: Vec<&str> = packages
+            .iter()
+            .filter(|record| record.status == PackageStatus::Outdated)
+            .map(|record| record.name.as_str())
+            .collect();
+        if !outdated.is_empty() {
+            match brew_download_sizes(options, &outdated) {
+                Ok(sizes) => {
+                    for record in &mut packages {
+                        record.download_size = sizes.get(&record.name).copied();
+                    }
+                }
+                Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+                Err(err) => log::warn!("brew: download size lookup failed: {err}"),
+            }
+        }
+    }
+
+    Ok(packages)
+}
+
+/// Lists formulae that no other installed formula depends on with `brew leaves`, which prints
+/// third-party taps in the full `user/tap/name` form.
+fn brew_leaves(options: &CollectInventoryOptions) -> Result<HashSet<String>, CollectionError> {
+    let leaves_output = run_command(options, "brew", &["leaves"], None::<&[i32]>)?;
+    ensure_success(&leaves_output, "brew leaves")?;
+    Ok(leaves_output
+        .stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.rsplit('/').next().unwrap_or(line).to_string())
+        .collect())
+}
+
+/// Reads bottle sizes for `formulae` from `brew info --json=v2`, keyed by name.
+fn brew_download_sizes(
+    options: &CollectInventoryOptions,
+    formulae: &[&str],
+) -> Result<HashMap<String, u64>, CollectionError> {
+    let mut args = vec!["info", "--json=v2"];
+    args.extend_from_slice(formulae);
+    let info_output = run_command(options, "brew", &args, None::<&[i32]>)?;
+    ensure_success(&info_output, "brew info --json=v2")?;
+    parse_brew_download_sizes(&info_output.stdout)
+}
+
+/// Takes each formula's `all` bottle size, or else its largest per-platform bottle as an upper
+/// bound. Formulae without a sized bottle (built from source) are left out.
+fn parse_brew_download_sizes(stdout: &str) -> Result<HashMap<String, u64>, CollectionError> {
+    #[derive(Debug, Deserialize)]
+    struct BrewInfo {
+        #[serde(default)]
+        formulae: Vec<BrewInfoFormula>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewInfoFormula {
+        name: String,
+        #[serde(default)]
+        bottle: BTreeMap<String, BrewBottle>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewBottle {
+        #[serde(default)]
+        files: BTreeMap<String, BrewBottleFile>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewBottleFile {
+        size: Option<u64>,
+    }
+
+    let info: BrewInfo = serde_json::from_str(stdout)?;
+    Ok(info
+        .formulae
+        .into_iter()
+        .filter_map(|formula| {
+            let files = &formula.bottle.get("stable")?.files;
+            let size = match files.get("all").and_then(|file| file.size) {
+                Some(size) => size,
+                None => files.values().filter_map(|file| file.size).max()?,
+            };
+            Some((formula.name, size))
+        })
+        .collect())
+}
+
+/// Reads formula and cask homepages from `brew info --json=v2 --installed`, keyed by name.
+fn brew_homepages(
+    options: &CollectInventoryOptions,
+) -> Result<HashMap<String, String>, CollectionError> {
+    let info_output = run_command(
+        options,
+        "brew",
+        &["info", "--json=v2", "--installed"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&info_output, "brew info --json=v2 --installed")?;
+    parse_brew_homepages(&info_output.stdout)
+}
+
+fn parse_brew_homepages(stdout: &str) -> Result<HashMap<String, String>, CollectionError> {
+    #[derive(Debug, Deserialize)]
+    struct BrewInfo {
+        #[serde(default)]
+        formulae: Vec<BrewInfoFormula>,
+        #[serde(default)]
+        casks: Vec<BrewInfoCask>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewInfoFormula {
+        name: String,
+        homepage: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct BrewInfoCask {
+        token: String,
+        homepage: Option<String>,
+    }
+
+    if stdout.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let info: BrewInfo = serde_json::from_str(stdout)?;
+    let formulae = info
+        .formulae
+        .into_iter()
+        .filter_map(|formula| Some((formula.name, formula.homepage?)));
+    let casks = info
+        .casks
+        .into_iter()
+        .filter_map(|cask| Some((cask.token, cask.homepage?)));
+    Ok(formulae
+        .chain(casks)
+        .filter(|(_, homepage)| !homepage.is_empty())
+        .collect())
+}
+
+fn collect_npm(options: &CollectInventoryOptions) -> Result<Vec<PackageRecord>, CollectionError> {
+    let depth = format!("--depth={}", options.npm_depth);
+    // npm exits 1 on peer-dependency problems while still printing the full tree, so only an
+    // empty stdout counts as failure.
+    let list_output = run_command(
+        options,
+        "npm",
+        &["ls", "-g", &depth, "--json"],
+        Some(&[0, 1]),
+    )?;
+    if list_output.exit_allowed && list_output.stdout.trim().is_empty() {
+        return Err(CollectionError::Command(CommandError::from_status(
+            format!("npm ls -g {depth} --json"),
+            list_output.status.code(),
+            list_output.stderr,
+        )));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct NpmTree {
+        #[serde(default)]
+        dependencies: BTreeMap<String, NpmPackage>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct NpmPackage {
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        resolved: Option<String>,
+        #[serde(default)]
+        link: bool,
+        #[serde(default)]
+        dependencies: BTreeMap<String, NpmPackage>,
+    }
+
+    let tree: NpmTree = serde_json::from_str(&list_output.stdout)?;
+
+    let outdated_output =
+        run_command(options, "npm", &["outdated", "-g", "--json"], Some(&[0, 1]))?;
+    // npm returns exit code 1 when outdated packages exist; treat 0/1 as success.
+    let mut outdated_map: HashMap<String, String> = HashMap::new();
+    if !outdated_output.stdout.trim().is_empty() {
+        let value: serde_json::Value = serde_json::from_str(&outdated_output.stdout)?;
+        if let serde_json::Value::Object(entries) = value {
+            for (name, details) in entries {
+                if let Some(latest) = details.get("latest").and_then(|v| v.as_str()) {
+                    outdated_map.insert(name, latest.to_string());
+                }
+            }
+        }
+    }
+
+    // Walk breadth-first so a package installed directly wins over copies nested deeper.
+    let mut queue: VecDeque<(String, NpmPackage, bool)> = tree
+        .dependencies
+        .into_iter()
+        .map(|(name, pkg)| (name, pkg, false))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut flattened = Vec::new();
+    while let Some((name, mut pkg, nested)) = queue.pop_front() {
+        let children = std::mem::take(&mut pkg.dependencies);
+        queue.extend(children.into_iter().map(|(name, pkg)| (name, pkg, true)));
+        if seen.insert(name.clone()) {
+            flattened.push((name, pkg, nested));
+        }
+    }
+
+    let mut packages: Vec<PackageRecord> = flattened
+        .into_iter()
+        .filter_map(|(name, pkg, nested)| {
+            // `npm link` entries point at a local checkout, reported either as a `file:` resolution
+            // or with `link: true` depending on the npm version.
+            let linked = pkg.link
+                || pkg
+                    .resolved
+                    .as_deref()
+                    .is_some_and(|resolved| resolved.starts_with("file:"));
+            pkg.version.map(|current_version| {
+                // Linked packages track a local checkout, so the registry's latest does not apply.
+                let (latest_version, status) = if linked {
+                    (None, PackageStatus::Unknown)
+                } else {
+                    match outdated_map.get(&name) {
+                        Some(latest) => (Some(latest.clone()), PackageStatus::Outdated),
+                        // `npm outdated -g` only reports top-level packages.
+                        None if nested => (None, PackageStatus::Unknown),
+                        None => (None, PackageStatus::Current),
+                    }
+                };
+
+                PackageRecord {
+                    name,
+                    current_version,
+                    latest_version,
+                    installed_at: None,
+                    status,
+                    manager: PackageManager::Npm,
+                    pinned: false,
+                    dependency: nested,
+                    linked,
+                    source: None,
+                    homepage: None,
+                    deprecated: None,
+                    provenance: None,
+                    download_size: None,
+                }
+            })
+        })
+        .collect();
+
+    if options.deprecations {
+        for record in packages.iter_mut().filter(|record| !record.linked) {
+            match npm_deprecation(options, record) {
+                Ok(deprecated) => record.deprecated = deprecated,
+                Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+                Err(err) => log::warn!("npm: deprecation lookup for {} failed: {err}", record.name),
+            }
+        }
+    }
+
+    Ok(packages)
+}
+
+/// Looks up the deprecation notice of the installed version with `npm view <name>@<version>`.
+fn npm_deprecation(
+    options: &CollectInventoryOptions,
+    record: &PackageRecord,
+) -> Result<Option<String>, CollectionError> {
+    let spec = format!("{}@{}", record.name, record.current_version);
+    let view_output = run_command(options, "npm", &["view", &spec, "--json"], None::<&[i32]>)?;
+    ensure_success(&view_output, &format!("npm view {spec} --json"))?;
+    parse_npm_deprecated(&view_output.stdout)
+}
+
+fn parse_npm_deprecated(stdout: &str) -> Result<Option<String>, CollectionError> {
+    #[derive(Debug, Deserialize)]
+    struct NpmView {
+        #[serde(default)]
+        deprecated: Option<String>,
+    }
+
+    // A spec matching several versions yields an array; an installed version matches one.
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum NpmViewOutput {
+        One(NpmView),
+        Many(Vec<NpmView>),
+    }
+
+    if stdout.trim().is_empty() {
+        return Ok(None);
+    }
+    let deprecated = match serde_json::from_str(stdout)? {
+        NpmViewOutput::One(view) => view.deprecated,
+        NpmViewOutput::Many(views) => views.into_iter().find_map(|view| view.deprecated),
+    };
+    Ok(deprecated.filter(|message| !message.is_empty()))
+}
+
+fn collect_pip(options: &CollectInventoryOptions) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "pip", &["list", "--format=json"], None::<&[i32]>)?;
+    ensure_success(&list_output, "pip list --format=json")?;
+
+    #[derive(Debug, Deserialize)]
+    struct PipPackage {
+        name: String,
+        version: String,
+    }
+
+    let installed: Vec<PipPackage> = serde_json::from_str(&list_output.stdout)?;
+
+    // `pip list` covers both site directories; the user-site listing says which packages live
+    // there. Interpreters without a user site (e.g. inside a virtualenv) may reject `--user`, in
+    // which case every package counts as system-wide.
+    let user: Vec<PipPackage> = match run_command(
+        options,
+        "pip",
+        &["list", "--user", "--format=json"],
+        None::<&[i32]>,
+    ) {
+        Ok(output) if !output.stdout.trim().is_empty() => serde_json::from_str(&output.stdout)?,
+        Ok(_) => Vec::new(),
+        Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+        Err(err) => {
+            log::debug!("pip: no user-site packages: {err}");
+            Vec::new()
+        }
+    };
+    // A package in both scopes is reported once, with the user-site version that shadows the
+    // system one.
+    let user_names: HashSet<String> = user.iter().map(|pkg| pkg.name.clone()).collect();
+    let installed: Vec<(PipPackage, &str)> = installed
+        .into_iter()
+        .filter(|pkg| !user_names.contains(&pkg.name))
+        .map(|pkg| (pkg, "system"))
+        .chain(user.into_iter().map(|pkg| (pkg, "user")))
+        .collect();
+
+    #[derive(Debug, Deserialize)]
+    struct PipOutdated {
+        name: String,
+        #[serde(rename = "latest_version")]
+        latest_version: String,
+    }
+
+    let outdated_output = run_command(
+        options,
+        "pip",
+        &["list", "--outdated", "--format=json"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&outdated_output, "pip list --outdated --format=json")?;
+
+    let mut outdated_map: HashMap<String, String> = HashMap::new();
+    if !outdated_output.stdout.trim().is_empty() {
+        let outdated: Vec<PipOutdated> = serde_json::from_str(&outdated_output.stdout)?;
+        for pkg in outdated {
+            outdated_map.insert(pkg.name, pkg.latest_version);
+        }
+    }
+
+    let packages = installed
+        .into_iter()
+        .map(|(pkg, scope)| {
+            let latest_version = outdated_map.get(&pkg.name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name: pkg.name,
+                current_version: pkg.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Pip,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: Some(scope.to_string()),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+fn ensure_success(output: &CommandResult, label: &str) -> Result<(), CollectionError> {
+    if output.status.success() || output.exit_allowed {
+        Ok(())
+    } else {
+        Err(CollectionError::Command(CommandError::from_status(
+            label.to_string(),
+            output.status.code(),
+            output.stderr.clone(),
+        )))
+    }
+}
+
+fn run_command(
+    options: &CollectInventoryOptions,
+    program: &str,
+    args: &[&str],
+    allowed_exit_codes: Option<&[i32]>,
+) -> Result<CommandResult, CollectionError> {
+    run_command_in(options, None, program, args, allowed_exit_codes)
+}
+
+/// Like [`run_command`], but runs the command from `dir` when given, for project-scoped tools.
+///
+/// Without `dir`, both fall back to [`CollectInventoryOptions::working_dir`].
+fn run_command_in(
+    options: &CollectInventoryOptions,
+    dir: Option<&Path>,
+    program: &str,
+    args: &[&str],
+    allowed_exit_codes: Option<&[i32]>,
+) -> Result<CommandResult, CollectionError> {
+    let command_line = format!("{} {}", program, args.join(" "));
+    let allowed_exit_codes = options
+        .allowed_exit_codes
+        .get(&command_line)
+        .map(Vec::as_slice)
+        .or(allowed_exit_codes);
+
+    RAN_COMMANDS.with(|ran| {
+        if let Some(ran) = ran.borrow_mut().as_mut() {
+            ran.push(command_line.clone());
+        }
+    });
+
+    // Raw output is kept once per command, from its final attempt.
+    let recorded = RAW_OUTPUTS.with(|raw| raw.borrow().as_ref().map_or(0, Vec::len));
+    let attempts = options.command_retries.saturating_add(1);
+    let mut attempt = 1;
+    loop {
+        match run_command_once(
+            options,
+            dir,
+            program,
+            args,
+            &command_line,
+            allowed_exit_codes,
+        ) {
+            Err(CollectionError::Command(err @ CommandError::Status { .. }))
+                if attempt < attempts =>
+            {
+                log::debug!("`{command_line}` failed (attempt {attempt} of {attempts}): {err}");
+                RAW_OUTPUTS.with(|raw| {
+                    if let Some(raw) = raw.borrow_mut().as_mut() {
+                        raw.truncate(recorded);
+                    }
+                });
+                attempt += 1;
+            }
+            Err(CollectionError::Command(err)) if attempt > 1 => {
+                return Err(CollectionError::Command(CommandError::Retried {
+                    attempts: attempt,
+                    last: Box::new(err),
+                }));
+            }
+            result => {
+                if result.is_ok() && attempt > 1 {
+                    log::info!("`{command_line}` succeeded after {attempt} attempts");
+                }
+                return result;
+            }
+        }
+    }
+}
+
+/// Runs `command_line` once; [`run_command_in`] adds retries on top.
+fn run_command_once(
+    options: &CollectInventoryOptions,
+    dir: Option<&Path>,
+    program: &str,
+    args: &[&str],
+    command_line: &str,
+    allowed_exit_codes: Option<&[i32]>,
+) -> Result<CommandResult, CollectionError> {
+    let mut command = Command::new(program);
+    command.args(args).envs(&options.env);
+    if let Some(dir) = dir.or(options.working_dir.as_deref()) {
+        command.current_dir(dir);
+    }
+
+    let started = Instant::now();
+    let output = cancel::output_unless_cancelled(&mut command, &options.cancel)
+        .map_err(|source| {
+            log::debug!("failed to spawn `{command_line}`: {source}");
+            CollectionError::Command(CommandError::Spawn {
+                program: program.to_string(),
+                source,
+            })
+        })?
+        .ok_or_else(|| {
+            log::debug!("killed `{command_line}` after cancellation");
+            CollectionError::Cancelled
+        })?;
+    log::debug!(
+        "ran `{command_line}`: exit code {:?} in {:?}",
+        output.status.code(),
+        started.elapsed()
+    );
+
+    let decode = |bytes: Vec<u8>| {
+        if options.lossy_utf8 {
+            return Ok(String::from_utf8_lossy(&bytes).into_owned());
+        }
+        String::from_utf8(bytes).map_err(|source| {
+            CollectionError::Command(CommandError::Utf8 {
+                program: program.to_string(),
+                source,
+            })
+        })
+    };
+    let stdout = decode(output.stdout)?;
+    let stderr = decode(output.stderr)?;
+
+    RAW_OUTPUTS.with(|raw| {
+        if let Some(raw) = raw.borrow_mut().as_mut() {
+            raw.push(stdout.clone());
+        }
+    });
+
+    if !output.status.success() {
+        if let Some(codes) = allowed_exit_codes {
+            if let Some(code) = output.status.code() {
+                if codes.contains(&code) {
+                    return Ok(CommandResult {
+                        stdout,
+                        stderr,
+                        status: output.status,
+                        exit_allowed: true,
+                    });
+                }
+            }
+        }
+
+        return Err(CollectionError::Command(CommandError::from_status(
+            command_line.to_string(),
+            output.status.code(),
+            stderr,
+        )));
+    }
+
+    Ok(CommandResult {
+        stdout,
+        stderr,
+        status: output.status,
+        exit_allowed: false,
+    })
+}
+
+#[derive(Debug)]
+struct CommandResult {
+    stdout: String,
+    stderr: String,
+    status: ExitStatus,
+    /// Set when a non-zero exit code was accepted through the allowed exit codes.
+    exit_allowed: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum CollectionError {
+    #[error(transparent)]
+    Command(#[from] CommandError),
+    #[error("json parse error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "zypper")]
+    #[error("xml parse error: {0}")]
+    Xml(#[from] quick_xml::Error),
+    /// The manager's collector was compiled out of this build.
+    #[error("{} support requires the `{feature}` feature", manager.as_str())]
+    FeatureDisabled {
+        manager: PackageManager,
+        feature: &'static str,
+    },
+    /// [`upgrade_manager`] has no bulk upgrade command for the manager.
+    #[error("upgrading {} packages is not supported", .0.as_str())]
+    UpgradeUnsupported(PackageManager),
+    /// A custom manager was selected without a [`Collector`] to run for it.
+    #[error("no collector is registered for {}", .0.as_str())]
+    NoCollector(PackageManager),
+    /// [`CollectInventoryOptions::cancel`] was triggered before or while the manager ran.
+    #[error("collection was cancelled")]
+    Cancelled,
+}
+
+/// Returned when parsing a manager name that bagpack does not know about.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown package manager: {0}")]
+pub struct UnknownManagerError(pub String);
+
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("failed to spawn {program}: {source}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{program} exited with status {code:?}: {stderr}")]
+    Status {
+        program: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    #[error("{program} produced invalid UTF-8: {source}")]
+    Utf8 {
+        program: String,
+        #[source]
+        source: std::string::FromUtf8Error,
+    },
+    /// The command failed because it needs elevated privileges. Bagpack never escalates on its
+    /// own; UIs can ask the user to re-run with the required permissions.
+    #[error("{program} needs elevated privileges (exit status {code:?}): {stderr}")]
+    PermissionDenied {
+        program: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    /// The command kept failing through [`CollectInventoryOptions::command_retries`]; `last` is
+    /// the final attempt's error.
+    #[error("failed after {attempts} attempts: {last}")]
+    Retried {
+        attempts: u32,
+        #[source]
+        last: Box<CommandError>,
+    },
+}
+
+/// Lowercase stderr fragments that managers print when they need root.
+const PERMISSION_DENIED_PATTERNS: &[&str] = &[
+    "permission denied",
+    "operation not permitted",
+    "are you root",
+    "you must be root",
+    "must be run as root",
+    "need to be root",
+    "requires root",
+    "root privileges",
+    "insufficient privileges",
+    "unless you are root",
+    "eacces",
+];
+
+impl CommandError {
+    /// Builds the error for a disallowed exit status, recognising permission failures.
+    fn from_status(program: String, code: Option<i32>, stderr: String) -> Self {
+        let lowered = stderr.to_lowercase();
+        if PERMISSION_DENIED_PATTERNS
+            .iter()
+            .any(|pattern| lowered.contains(pattern))
+        {
+            CommandError::PermissionDenied {
+                program,
+                code,
+                stderr,
+            }
+        } else {
+            CommandError::Status {
+                program,
+                code,
+                stderr,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use time::format_description::well_known::Rfc3339;
+    use time::OffsetDateTime;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use super::{
+        demo_snapshot, run_bounded, run_command, CollectInventoryOptions, CollectionError,
+        CollectionSummary, CollectionWarning, CommandError, InventorySnapshot, PackageManager,
+        PackageRecord, PackageStatus, UpdateDebt, UpdateKind, REDACTED_NAME,
+    };
+
+    fn record(name: &str, manager: PackageManager) -> PackageRecord {
+        PackageRecord::builder(name, manager)
+            .current_version("1.0.0")
+            .status(PackageStatus::Current)
+            .build()
+    }
+
+    #[test]
+    fn recompute_status_follows_the_versions() {
+        let with_latest = |current: &str, latest: &str, status| PackageRecord {
+            current_version: current.into(),
+            latest_version: Some(latest.into()),
+            status,
+            ..record("wget", PackageManager::Brew)
+        };
+
+        // A formatting quirk such as a `v` prefix is not an update.
+        let mut quirk = with_latest("1.24.5", "v1.24.5", PackageStatus::Outdated);
+        quirk.recompute_status();
+        assert_eq!(quirk.status, PackageStatus::Current);
+
+        let mut newer = with_latest("1.24.5", "1.24.6", PackageStatus::Current);
+        newer.pinned = true;
+        newer.recompute_status();
+        assert_eq!(newer.status, PackageStatus::Outdated);
+
+        let mut ahead = with_latest("1.25.0", "1.24.6", PackageStatus::Outdated);
+        ahead.recompute_status();
+        assert_eq!(ahead.status, PackageStatus::Ahead);
+
+        let mut unknown = with_latest(super::UNKNOWN_VERSION, "1.24.6", PackageStatus::Outdated);
+        unknown.recompute_status();
+        assert_eq!(unknown.status, PackageStatus::Unknown);
+
+        let mut linked = with_latest("1.0.0", "2.0.0", PackageStatus::Outdated);
+        linked.linked = true;
+        linked.recompute_status();
+        assert_eq!(linked.status, PackageStatus::Unknown);
+
+        let mut no_latest = PackageRecord {
+            status: PackageStatus::Outdated,
+            ..record("org.gimp.GIMP", PackageManager::Flatpak)
+        };
+        no_latest.recompute_status();
+        assert_eq!(no_latest.status, PackageStatus::Outdated);
+    }
+
+    #[test]
+    fn counts_outdated_packages() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(
+            PackageRecord::builder("wget", PackageManager::Brew)
+                .current_version("1.24.5")
+                .latest_version("1.24.6")
+                .status(PackageStatus::Outdated)
+                .build(),
+        );
+        snapshot.push(
+            PackageRecord::builder("typescript", PackageManager::Npm)
+                .current_version("5.5.2")
+                .latest_version("5.6.3")
+                .status(PackageStatus::Current)
+                .build(),
+        );
+        snapshot.set_generated_at("2025-10-05T00:00:00Z");
+
+        assert_eq!(snapshot.outdated_count(), 1);
+        assert_eq!(
+            snapshot.generated_at.as_deref(),
+            Some("2025-10-05T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn redacts_prefix_matches() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(record("@acme/deploy", PackageManager::Npm));
+        snapshot.push(record("typescript", PackageManager::Npm));
+
+        snapshot.redact(&["@acme/".to_string()]);
+
+        assert_eq!(snapshot.packages[0].name, REDACTED_NAME);
+        assert_eq!(snapshot.packages[1].name, "typescript");
+    }
+
+    #[test]
+    fn redacts_wildcard_matches() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(record("acme-billing-internal", PackageManager::Pip));
+        snapshot.push(record("internal-tools", PackageManager::Pip));
+
+        snapshot.redact(&["*-internal".to_string()]);
+
+        assert_eq!(snapshot.packages[0].name, REDACTED_NAME);
+        assert_eq!(snapshot.packages[1].name, "internal-tools");
+    }
+
+    #[test]
+    fn finds_duplicate_versions_within_a_manager() {
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(record("typescript", PackageManager::Npm));
+        snapshot.push(PackageRecord {
+            current_version: "5.6.3".into(),
+            ..record("typescript", PackageManager::Npm)
+        });
+        snapshot.push(record("typescript", PackageManager::Brew));
+
+        assert_eq!(
+            snapshot.duplicates(),
+            vec![(
+                PackageManager::Npm,
+                "typescript".to_string(),
+                vec!["1.0.0".to_string(), "5.6.3".to_string()],
+            )]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn forwards_extra_env_to_commands() {
+        let mut options = CollectInventoryOptions::default();
+        options
+            .env
+            .insert("BAGPACK_TEST_VALUE".into(), "forwarded".into());
+
+        let output = run_command(
+            &options,
+            "sh",
+            &["-c", "printf %s \"$BAGPACK_TEST_VALUE\""],
+            None::<&[i32]>,
+        )
+        .unwrap();
+
+        assert_eq!(output.stdout, "forwarded");
+    }
+
+    #[test]
+    fn permission_denied_stderr_is_classified() {
+        let stderr = "E: Could not open lock file /var/lib/dpkg/lock-frontend - open (13: Permission denied)\n\
+                      E: Unable to acquire the dpkg frontend lock, are you root?\n";
+
+        let error =
+            CommandError::from_status("apt list --upgradable".into(), Some(100), stderr.into());
+
+        assert!(matches!(
+            error,
+            CommandError::PermissionDenied {
+                code: Some(100),
+                ..
+            }
+        ));
+        assert!(matches!(
+            CommandError::from_status("pip list".into(), Some(1), "No module named pip".into()),
+            CommandError::Status { .. }
+        ));
+    }
+
+    #[test]
+    fn failing_command_surfaces_permission_denied() {
+        let result = run_command(
+            &CollectInventoryOptions::default(),
+            "sh",
+            &[
+                "-c",
+                "echo 'error: you cannot perform this operation unless you are root.' >&2; exit 1",
+            ],
+            None::<&[i32]>,
+        );
+
+        assert!(matches!(
+            result,
+            Err(CollectionError::Command(
+                CommandError::PermissionDenied { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn staleness_is_measured_against_installed_at() {
+        let now = OffsetDateTime::parse("2025-10-05T00:00:00Z", &Rfc3339).unwrap();
+        let threshold = Duration::from_secs(365 * 24 * 60 * 60);
+        let installed = |timestamp: &str| PackageRecord {
+            installed_at: Some(timestamp.into()),
+            ..record("wget", PackageManager::Brew)
+        };
+
+        assert!(installed("2024-10-04T23:59:59Z").is_stale_at(threshold, now));
+        assert!(!installed("2024-10-05T00:00:00Z").is_stale_at(threshold, now));
+        assert!(!installed("2025-06-01T00:00:00Z").is_stale_at(threshold, now));
+    }
+
+    #[test]
+    fn installed_since_includes_cutoff_and_skips_unparseable_dates() {
+        let cutoff = OffsetDateTime::parse("2025-02-11T15:10:30Z", &Rfc3339).unwrap();
+        let mut snapshot = demo_snapshot();
+        snapshot.push(PackageRecord {
+            installed_at: Some("last spring".into()),
+            ..record("jq", PackageManager::Brew)
+        });
+
+        let names: Vec<&str> = snapshot
+            .installed_since(cutoff)
+            .iter()
+            .map(|record| record.name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["typescript"]);
+        assert_eq!(
+            snapshot
+                .installed_since(OffsetDateTime::parse("2020-01-01T00:00:00Z", &Rfc3339).unwrap())
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn oldest_and_newest_skip_missing_timestamps_and_break_ties_by_name() {
+        let installed = |name: &str, timestamp: Option<&str>| PackageRecord {
+            installed_at: timestamp.map(Into::into),
+            ..record(name, PackageManager::Brew)
+        };
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(installed("wget", Some("2024-09-17T08:22:00Z")));
+        snapshot.push(installed("make", None));
+        snapshot.push(installed("jq", Some("2021-03-01T00:00:00Z")));
+        snapshot.push(installed("zsh", Some("2025-02-11T15:10:30Z")));
+        snapshot.push(installed("git", Some("2025-02-11T15:10:30Z")));
+        snapshot.push(installed("curl", Some("last spring")));
+
+        assert_eq!(
+            snapshot.oldest().map(|record| record.name.as_str()),
+            Some("jq")
+        );
+        assert_eq!(
+            snapshot.newest().map(|record| record.name.as_str()),
+            Some("git")
+        );
+
+        let mut undated = InventorySnapshot::default();
+        undated.push(installed("make", None));
+        assert_eq!(undated.oldest(), None);
+        assert_eq!(undated.newest(), None);
+    }
+
+    #[test]
+    fn search_tolerates_typos_and_ranks_best_first() {
+        let mut snapshot = demo_snapshot();
+        snapshot.push(record("typesync", PackageManager::Npm));
+        snapshot.push(record("@types/node", PackageManager::Npm));
+
+        let names = |query: &str| -> Vec<String> {
+            snapshot
+                .search(query)
+                .into_iter()
+                .map(|(record, _)| record.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names("typscript")[0], "typescript");
+        assert_eq!(
+            names("types"),
+            vec!["typesync", "typescript", "@types/node"]
+        );
+        assert!(names("kubectl").is_empty());
+
+        let results = snapshot.search("TypeScript");
+        assert_eq!(results[0].1, 1.0);
+        assert!(results.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn len_and_is_empty_delegate_to_packages() {
+        let snapshot = demo_snapshot();
+
+        assert_eq!(snapshot.len(), snapshot.packages.len());
+        assert!(!snapshot.is_empty());
+        assert_eq!(InventorySnapshot::default().len(), 0);
+        assert!(InventorySnapshot::default().is_empty());
+    }
+
+    #[test]
+    fn same_identity_ignores_installed_at_and_versions() {
+        let wget = record("wget", PackageManager::Brew);
+        let reinstalled = PackageRecord {
+            installed_at: Some("2025-10-05T00:00:00Z".into()),
+            ..wget.clone()
+        };
+        let upgraded = PackageRecord {
+            current_version: "1.24.6".into(),
+            ..wget.clone()
+        };
+
+        assert_ne!(wget, reinstalled);
+        assert!(wget.same_identity(&reinstalled));
+        assert!(wget.same_version(&reinstalled));
+        assert!(wget.same_identity(&upgraded));
+        assert!(!wget.same_version(&upgraded));
+        assert!(!wget.same_identity(&record("wget", PackageManager::MacPorts)));
+        assert!(!wget.same_identity(&record("curl", PackageManager::Brew)));
+    }
+
+    #[test]
+    fn records_without_installed_at_are_never_stale() {
+        let now = OffsetDateTime::parse("2025-10-05T00:00:00Z", &Rfc3339).unwrap();
+        let unparseable = PackageRecord {
+            installed_at: Some("last spring".into()),
+            ..record("wget", PackageManager::Brew)
+        };
+
+        assert!(!record("wget", PackageManager::Brew).is_stale_at(Duration::ZERO, now));
+        assert!(!unparseable.is_stale_at(Duration::ZERO, now));
+    }
+
+    #[test]
+    fn age_is_measured_from_generated_at() {
+        let now = OffsetDateTime::parse("2025-10-05T14:34:56Z", &Rfc3339).unwrap();
+        let mut snapshot = demo_snapshot();
+
+        assert_eq!(snapshot.age(now), Some(Duration::from_secs(2 * 60 * 60)));
+
+        snapshot.set_generated_at("2025-10-05T15:00:00Z");
+        assert_eq!(snapshot.age(now), Some(Duration::ZERO));
+
+        snapshot.set_generated_at("yesterday");
+        assert_eq!(snapshot.age(now), None);
+        assert_eq!(InventorySnapshot::default().age(now), None);
+    }
+
+    #[test]
+    fn content_hash_ignores_generated_at() {
+        let snapshot = demo_snapshot();
+        let mut rescanned = demo_snapshot();
+        rescanned.set_generated_at("2030-01-01T00:00:00Z");
+
+        assert_eq!(snapshot.content_hash(), rescanned.content_hash());
+        rescanned.packages[0].current_version = "1.24.6".into();
+        assert_ne!(snapshot.content_hash(), rescanned.content_hash());
+    }
+
+    #[test]
+    fn managers_lists_each_represented_manager_once() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages.reverse();
+        snapshot.push(record("jq", PackageManager::Brew));
+
+        assert_eq!(
+            snapshot.managers(),
+            vec![
+                PackageManager::Brew,
+                PackageManager::Npm,
+                PackageManager::Pip
+            ]
+        );
+        assert!(InventorySnapshot::default().managers().is_empty());
+    }
+
+    #[test]
+    fn groups_demo_snapshot_by_manager() {
+        let snapshot = demo_snapshot();
+        let groups = snapshot.group_by_manager();
+        let names = |manager| {
+            groups[&manager]
+                .iter()
+                .map(|record| record.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            groups.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                PackageManager::Brew,
+                PackageManager::Npm,
+                PackageManager::Pip
+            ]
+        );
+        assert_eq!(names(PackageManager::Brew), vec!["wget"]);
+        assert_eq!(names(PackageManager::Npm), vec!["typescript"]);
+        assert_eq!(names(PackageManager::Pip), vec!["requests"]);
+    }
+
+    #[test]
+    fn enums_sort_in_declaration_order() {
+        let mut statuses = vec![
+            PackageStatus::Unknown,
+            PackageStatus::Outdated,
+            PackageStatus::Current,
+            PackageStatus::Ahead,
+        ];
+        statuses.sort();
+        assert_eq!(
+            statuses,
+            vec![
+                PackageStatus::Current,
+                PackageStatus::Ahead,
+                PackageStatus::Outdated,
+                PackageStatus::Unknown
+            ]
+        );
+
+        let mut managers = vec![
+            PackageManager::DotnetTool,
+            PackageManager::Pip,
+            PackageManager::Brew,
+            PackageManager::Npm,
+        ];
+        managers.sort();
+        assert_eq!(
+            managers,
+            vec![
+                PackageManager::Brew,
+                PackageManager::Npm,
+                PackageManager::Pip,
+                PackageManager::DotnetTool
+            ]
+        );
+    }
+
+    #[test]
+    fn manager_names_round_trip_through_serde() {
+        for manager in PackageManager::ALL {
+            let serialized = serde_json::to_value(manager).unwrap();
+
+            assert_eq!(serialized, manager.as_str());
+            assert_eq!(
+                manager.as_str().parse::<PackageManager>(),
+                Ok(manager.clone())
+            );
+        }
+        assert!("apt-get".parse::<PackageManager>().is_err());
+    }
+
+    #[test]
+    fn other_managers_serialize_as_bare_names() {
+        let custom = PackageManager::Other("apt-get".into());
+
+        assert_eq!(serde_json::to_value(&custom).unwrap(), "apt-get");
+        assert_eq!(
+            serde_json::from_value::<PackageManager>("apt-get".into()).unwrap(),
+            custom
+        );
+        assert_eq!(
+            serde_json::from_value::<PackageManager>("brew".into()).unwrap(),
+            PackageManager::Brew
+        );
+        assert_eq!(custom.as_str(), "apt-get");
+    }
+
+    #[test]
+    fn npm_view_deprecation_message_is_extracted() {
+        let stdout = r#"{
+  "name": "request",
+  "version": "2.88.2",
+  "deprecated": "request has been deprecated, see https://github.com/request/request/issues/3142",
+  "dist-tags": {"latest": "2.88.2"}
+}"#;
+
+        assert_eq!(
+            super::parse_npm_deprecated(stdout).unwrap().as_deref(),
+            Some("request has been deprecated, see https://github.com/request/request/issues/3142")
+        );
+        assert_eq!(
+            super::parse_npm_deprecated(r#"{"name": "typescript", "version": "5.5.2"}"#).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn brew_info_bottle_sizes_prefer_the_all_bottle() {
+        let stdout = r#"{
+  "formulae": [
+    {"name": "wget", "bottle": {"stable": {"files": {
+      "arm64_sonoma": {"url": "https://ghcr.io/v2/homebrew/core/wget/blobs/sha256:1", "size": 1512345},
+      "x86_64_linux": {"url": "https://ghcr.io/v2/homebrew/core/wget/blobs/sha256:2", "size": 1623456}
+    }}}},
+    {"name": "ca-certificates", "bottle": {"stable": {"files": {
+      "all": {"url": "https://ghcr.io/v2/homebrew/core/ca-certificates/blobs/sha256:3", "size": 130000}
+    }}}},
+    {"name": "from-source", "bottle": {}}
+  ],
+  "casks": []
+}"#;
+
+        let sizes = super::parse_brew_download_sizes(stdout).unwrap();
+
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes["wget"], 1623456);
+        assert_eq!(sizes["ca-certificates"], 130000);
+    }
+
+    #[test]
+    fn estimated_download_bytes_sums_outdated_records() {
+        let mut snapshot = demo_snapshot();
+        for record in &mut snapshot.packages {
+            record.download_size = Some(1000);
+        }
+
+        // Only `wget` is outdated in the demo snapshot.
+        assert_eq!(snapshot.estimated_download_bytes(), 1000);
+        assert_eq!(demo_snapshot().estimated_download_bytes(), 0);
+    }
+
+    #[test]
+    fn brew_info_homepages_cover_formulae_and_casks() {
+        let stdout = r#"{
+  "formulae": [
+    {"name": "wget", "full_name": "wget", "homepage": "https://www.gnu.org/software/wget/", "versions": {"stable": "1.24.5"}},
+    {"name": "jq", "full_name": "jq", "homepage": "https://jqlang.github.io/jq/"},
+    {"name": "local-tool", "full_name": "acme/tap/local-tool", "homepage": null}
+  ],
+  "casks": [
+    {"token": "firefox", "homepage": "https://www.mozilla.org/firefox/"}
+  ]
+}"#;
+
+        let homepages = super::parse_brew_homepages(stdout).unwrap();
+
+        assert_eq!(homepages.len(), 3);
+        assert_eq!(homepages["wget"], "https://www.gnu.org/software/wget/");
+        assert_eq!(homepages["firefox"], "https://www.mozilla.org/firefox/");
+        assert!(!homepages.contains_key("local-tool"));
+    }
+
+    #[test]
+    fn only_outdated_keeps_metadata() {
+        let outdated = demo_snapshot().only_outdated();
+
+        assert_eq!(outdated.generated_at, demo_snapshot().generated_at);
+        assert_eq!(outdated.packages.len(), 1);
+        assert_eq!(outdated.packages[0].name, "wget");
+    }
+
+    #[test]
+    fn without_unknown_drops_undetermined_records() {
+        let known = demo_snapshot().without_unknown();
+
+        assert_eq!(known.generated_at, demo_snapshot().generated_at);
+        let names: Vec<&str> = known.packages.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["wget", "typescript"]);
+    }
+
+    #[test]
+    fn pages_preserve_metadata_and_total() {
+        let snapshot = demo_snapshot();
+
+        let first = snapshot.page(0, 2);
+        assert_eq!(first.generated_at, snapshot.generated_at);
+        assert_eq!(first.total, Some(3));
+        assert_eq!(
+            first
+                .packages
+                .iter()
+                .map(|record| record.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["wget", "typescript"]
+        );
+
+        let last = snapshot.page(2, 10);
+        assert_eq!(last.packages.len(), 1);
+        assert_eq!(last.packages[0].name, "requests");
+    }
+
+    #[test]
+    fn pages_out_of_range_are_empty() {
+        let snapshot = demo_snapshot();
+
+        assert!(snapshot.page(3, 10).packages.is_empty());
+        assert!(snapshot.page(usize::MAX, 10).packages.is_empty());
+        assert!(snapshot.page(0, 0).packages.is_empty());
+        assert_eq!(snapshot.page(0, 0).total, Some(3));
+    }
+
+    #[test]
+    fn explains_each_status() {
+        let snapshot = demo_snapshot();
+
+        assert_eq!(
+            snapshot.packages[0].explain(),
+            "wget is outdated: 1.24.5 installed, 1.24.6 available (patch update)"
+        );
+        assert_eq!(
+            snapshot.packages[1].explain(),
+            "typescript is up to date at 5.5.2"
+        );
+        assert_eq!(
+            snapshot.packages[2].explain(),
+            "requests: latest version unknown (2.32.3 installed)"
+        );
+
+        let no_latest = PackageRecord {
+            status: PackageStatus::Outdated,
+            ..record("jq", PackageManager::Brew)
+        };
+        assert_eq!(
+            no_latest.explain(),
+            "jq is outdated: 1.0.0 installed, latest version unknown"
+        );
+
+        let ahead = PackageRecord {
+            latest_version: Some("0.9.0".into()),
+            status: PackageStatus::Ahead,
+            ..record("jq", PackageManager::Brew)
+        };
+        assert_eq!(
+            ahead.explain(),
+            "jq is ahead of the published latest: 1.0.0 installed, 0.9.0 published"
+        );
+    }
+
+    fn warning(manager: PackageManager) -> CollectionWarning {
+        CollectionWarning {
+            manager,
+            message: "failed to spawn".into(),
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn all_failed_collection_is_a_total_failure() {
+        let summary = CollectionSummary {
+            attempted_managers: vec![PackageManager::Brew, PackageManager::Npm],
+            warnings: vec![warning(PackageManager::Brew), warning(PackageManager::Npm)],
+            ..CollectionSummary::default()
+        };
+
+        assert!(summary.is_total_failure());
+        assert!(summary.succeeded_managers().is_empty());
+    }
+
+    #[test]
+    fn partial_or_empty_collection_is_not_a_total_failure() {
+        let partial = CollectionSummary {
+            snapshot: demo_snapshot(),
+            attempted_managers: vec![
+                PackageManager::Brew,
+                PackageManager::Npm,
+                PackageManager::Pip,
+            ],
+            warnings: vec![warning(PackageManager::Npm)],
+            ..CollectionSummary::default()
+        };
+        assert!(!partial.is_total_failure());
+        assert_eq!(
+            partial.succeeded_managers(),
+            vec![PackageManager::Brew, PackageManager::Pip]
+        );
+
+        let nothing_installed = CollectionSummary {
+            attempted_managers: vec![PackageManager::Brew],
+            ..CollectionSummary::default()
+        };
+        assert!(!nothing_installed.is_total_failure());
+        assert_eq!(
+            nothing_installed.succeeded_managers(),
+            vec![PackageManager::Brew]
+        );
+    }
+
+    fn max_overlap(limit: usize, jobs: usize) -> (usize, Vec<usize>) {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let work = (0..jobs)
+            .map(|index| {
+                let (running, peak) = (&running, &peak);
+                move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    index
+                }
+            })
+            .collect();
+
+        let results = run_bounded(limit, work);
+        (peak.load(Ordering::SeqCst), results)
+    }
+
+    #[test]
+    fn concurrency_limit_of_one_runs_collectors_sequentially() {
+        let (peak, results) = max_overlap(1, 4);
+
+        assert_eq!(peak, 1);
+        assert_eq!(results, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn concurrency_limit_bounds_parallel_collectors() {
+        let (peak, results) = max_overlap(2, 6);
+
+        assert!(peak <= 2, "peak concurrency was {peak}");
+        assert_eq!(results, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(max_overlap(0, 2).0, 1);
+    }
+
+    #[test]
+    fn update_debt_counts_mixed_update_kinds() {
+        let update = |name: &str, current: &str, latest: Option<&str>| PackageRecord {
+            current_version: current.into(),
+            latest_version: latest.map(Into::into),
+            status: PackageStatus::Outdated,
+            ..record(name, PackageManager::Npm)
+        };
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(update("a", "1.4.2", Some("2.0.0")));
+        snapshot.push(update("b", "1.4.2", Some("1.5.0")));
+        snapshot.push(update("c", "1.4.2", Some("1.6.1")));
+        snapshot.push(update("d", "1.4.2", Some("1.4.3")));
+        snapshot.push(update("e", "1.4.2", None));
+        snapshot.push(record("f", PackageManager::Brew));
+
+        let debt = snapshot.update_debt();
+
+        assert_eq!(
+            debt,
+            UpdateDebt {
+                major: 1,
+                minor: 2,
+                patch: 1,
+            }
+        );
+        assert_eq!(debt.total(), 4);
+        assert_eq!(InventorySnapshot::default().update_debt().total(), 0);
+    }
+
+    #[test]
+    fn outdated_count_by_manager_includes_up_to_date_managers() {
+        let outdated = |name: &str, manager| PackageRecord {
+            status: PackageStatus::Outdated,
+            ..record(name, manager)
+        };
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(outdated("wget", PackageManager::Brew));
+        snapshot.push(record("jq", PackageManager::Brew));
+        snapshot.push(outdated("git", PackageManager::Brew));
+        snapshot.push(record("typescript", PackageManager::Npm));
+        snapshot.push(outdated("requests", PackageManager::Pip));
+
+        let counts = snapshot.outdated_count_by_manager();
+
+        assert_eq!(
+            counts,
+            HashMap::from([
+                (PackageManager::Brew, 2),
+                (PackageManager::Npm, 0),
+                (PackageManager::Pip, 1),
+            ])
+        );
+        assert_eq!(counts.values().sum::<usize>(), snapshot.outdated_count());
+    }
+
+    #[test]
+    fn partition_by_update_kind_buckets_outdated_records() {
+        let update = |name: &str, latest: &str| PackageRecord {
+            current_version: "1.4.2".into(),
+            latest_version: Some(latest.into()),
+            status: PackageStatus::Outdated,
+            ..record(name, PackageManager::Npm)
+        };
+        let mut snapshot = InventorySnapshot::default();
+        snapshot.push(update("a", "2.0.0"));
+        snapshot.push(update("b", "1.5.0"));
+        snapshot.push(update("c", "1.6.1"));
+        snapshot.push(update("d", "1.4.3"));
+        snapshot.push(update("e", "1.4.2"));
+        snapshot.push(record("f", PackageManager::Brew));
+
+        let partitions = snapshot.partition_by_update_kind();
+        let names = |kind| -> Vec<&str> {
+            partitions[&kind]
+                .iter()
+                .map(|record| record.name.as_str())
+                .collect()
+        };
+
+        assert_eq!(partitions.len(), 3);
+        assert_eq!(names(UpdateKind::Major), vec!["a"]);
+        assert_eq!(names(UpdateKind::Minor), vec!["b", "c"]);
+        assert_eq!(names(UpdateKind::Patch), vec!["d"]);
+    }
+
+    #[test]
+    fn warnings_group_by_manager() {
+        let mut summary = CollectionSummary::new(InventorySnapshot::default());
+        summary.warnings = vec![
+            warning(PackageManager::Npm),
+            CollectionWarning {
+                manager: PackageManager::Brew,
+                message: "brew outdated exited with status Some(1)".into(),
+                count: 1,
+            },
+            warning(PackageManager::Brew),
+        ];
+
+        let groups = summary.warnings_by_manager();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&PackageManager::Npm].len(), 1);
+        assert_eq!(
+            groups[&PackageManager::Brew],
+            vec![&summary.warnings[1], &summary.warnings[2]]
+        );
+    }
+
+    #[test]
+    fn duplicate_warnings_collapse_with_a_count() {
+        let mut summary = CollectionSummary::new(InventorySnapshot::default());
+        summary.warnings = vec![
+            warning(PackageManager::Brew),
+            warning(PackageManager::Npm),
+            warning(PackageManager::Brew),
+            warning(PackageManager::Brew),
+        ];
+
+        summary.dedupe_warnings();
+
+        assert_eq!(summary.warnings.len(), 2);
+        assert_eq!(summary.warnings[0].manager, PackageManager::Brew);
+        assert_eq!(summary.warnings[0].count, 3);
+        assert_eq!(summary.warnings[1].count, 1);
+        assert!(summary.warnings_json().contains(r#""count":3"#));
+    }
+
+    #[test]
+    fn warnings_json_contains_only_warnings() {
+        let mut summary = CollectionSummary::new(demo_snapshot());
+        summary.warnings = vec![warning(PackageManager::Pip)];
+
+        assert_eq!(
+            summary.warnings_json(),
+            r#"[{"manager":"pip","message":"failed to spawn"}]"#
+        );
+    }
+
+    #[test]
+    fn ignore_rules_apply_per_manager_or_globally() {
+        let mut snapshot = InventorySnapshot::default();
+        for (name, manager) in [
+            ("pip", PackageManager::Pip),
+            ("setuptools", PackageManager::Pip),
+            ("setuptools-scm", PackageManager::Pip),
+            ("pipx", PackageManager::Pip),
+            ("pip", PackageManager::Brew),
+            ("node-gyp", PackageManager::Npm),
+            ("node-gyp-build", PackageManager::Brew),
+        ] {
+            snapshot.push(record(name, manager));
+        }
+
+        snapshot.remove_ignored(&[
+            (Some(PackageManager::Pip), "pip".into()),
+            (Some(PackageManager::Pip), "setuptools*".into()),
+            (None, "node-gyp*".into()),
+        ]);
+
+        let remaining: Vec<(&str, PackageManager)> = snapshot
+            .packages
+            .iter()
+            .map(|record| (record.name.as_str(), record.manager.clone()))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![("pipx", PackageManager::Pip), ("pip", PackageManager::Brew)]
         );
     }
 }
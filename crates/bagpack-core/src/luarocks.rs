@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_luarocks(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "luarocks",
+        &["list", "--porcelain"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "luarocks list --porcelain")?;
+
+    let installed = parse_installed(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(
+        options,
+        "luarocks",
+        &["list", "--outdated", "--porcelain"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&outdated_output, "luarocks list --outdated --porcelain")?;
+
+    Ok(build_records(
+        installed,
+        &parse_outdated(&outdated_output.stdout),
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct InstalledRock {
+    name: String,
+    version: String,
+    tree: String,
+}
+
+/// Parses `luarocks list --porcelain` rows shaped like `name<TAB>version<TAB>status<TAB>tree`.
+fn parse_installed(stdout: &str) -> Vec<InstalledRock> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t').map(str::trim);
+            let name = columns.next().filter(|name| !name.is_empty())?;
+            let version = columns.next().filter(|version| !version.is_empty())?;
+            let _status = columns.next();
+            let tree = columns.next().unwrap_or_default();
+            Some(InstalledRock {
+                name: name.to_string(),
+                version: version.to_string(),
+                tree: tree.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parses `luarocks list --outdated --porcelain` rows shaped like
+/// `name<TAB>installed<TAB>available<TAB>repository`.
+fn parse_outdated(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t').map(str::trim);
+            let name = columns.next().filter(|name| !name.is_empty())?;
+            let _installed = columns.next()?;
+            let available = columns.next().filter(|version| !version.is_empty())?;
+            Some((name.to_string(), available.to_string()))
+        })
+        .collect()
+}
+
+/// Turns installed rocks into records tagged with the rocks tree they live in.
+fn build_records(
+    rocks: Vec<InstalledRock>,
+    outdated: &HashMap<String, String>,
+) -> Vec<PackageRecord> {
+    rocks
+        .into_iter()
+        .map(|rock| {
+            let latest_version = outdated.get(&rock.name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name: rock.name,
+                current_version: rock.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::LuaRocks,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: (!rock.tree.is_empty()).then_some(rock.tree),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_installed, parse_outdated, InstalledRock};
+    use crate::PackageStatus;
+
+    #[test]
+    fn luarocks_porcelain_list_reads_tab_separated_columns() {
+        let stdout = "lpeg\t1.1.0-1\tinstalled\t/usr/local/lib/luarocks/rocks-5.4\n\
+                      luafilesystem\t1.8.0-1\tinstalled\t/home/dev/.luarocks/lib/luarocks/rocks-5.4\n\
+                      \n";
+
+        assert_eq!(
+            parse_installed(stdout),
+            vec![
+                InstalledRock {
+                    name: "lpeg".into(),
+                    version: "1.1.0-1".into(),
+                    tree: "/usr/local/lib/luarocks/rocks-5.4".into(),
+                },
+                InstalledRock {
+                    name: "luafilesystem".into(),
+                    version: "1.8.0-1".into(),
+                    tree: "/home/dev/.luarocks/lib/luarocks/rocks-5.4".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn luarocks_porcelain_outdated_fills_latest_version() {
+        let installed = parse_installed(
+            "lpeg\t1.0.2-1\tinstalled\t/usr/local/lib/luarocks/rocks-5.4\n\
+             penlight\t1.14.0-1\tinstalled\t/usr/local/lib/luarocks/rocks-5.4\n",
+        );
+        let outdated = parse_outdated("lpeg\t1.0.2-1\t1.1.0-1\thttps://luarocks.org\n");
+
+        let records = build_records(installed, &outdated);
+
+        assert_eq!(records[0].status, PackageStatus::Outdated);
+        assert_eq!(records[0].latest_version.as_deref(), Some("1.1.0-1"));
+        assert_eq!(
+            records[0].source.as_deref(),
+            Some("/usr/local/lib/luarocks/rocks-5.4")
+        );
+        assert_eq!(records[1].status, PackageStatus::Current);
+        assert_eq!(records[1].latest_version, None);
+    }
+}
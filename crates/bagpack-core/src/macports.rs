@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_macports(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let installed_output = run_command(options, "port", &["installed"], None::<&[i32]>)?;
+    ensure_success(&installed_output, "port installed")?;
+
+    let installed = parse_installed(&installed_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(options, "port", &["outdated"], None::<&[i32]>)?;
+    ensure_success(&outdated_output, "port outdated")?;
+
+    let updates = parse_outdated(&outdated_output.stdout);
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = updates.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::MacPorts,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses the active ports from `port installed`, which prints an explanatory first line
+/// followed by indented rows like `  curl @8.5.0_0+ssl (active)`.
+///
+/// Inactive older versions are listed too but only the `(active)` one is kept. Variants
+/// (`+ssl`) are dropped from the version so it lines up with `port outdated`.
+fn parse_installed(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with(char::is_whitespace))
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let name = columns.next()?;
+            let version = columns.next()?.strip_prefix('@')?;
+            if !columns.any(|column| column == "(active)") {
+                return None;
+            }
+
+            let version = version.split('+').next().unwrap_or(version);
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Maps port names to the newer version from `port outdated` rows like
+/// `curl                           8.5.0_0 < 8.6.0_0`.
+///
+/// Nothing is pending when it prints "No installed ports are outdated."
+fn parse_outdated(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (installed, latest) = line.split_once(" < ")?;
+            let name = installed.split_whitespace().next()?;
+            let latest = latest.split_whitespace().next()?;
+            Some((name.to_string(), latest.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_installed, parse_outdated};
+
+    #[test]
+    fn macports_installed_keeps_active_versions_only() {
+        let stdout = "The following ports are currently installed:\n\
+                      \x20 curl @8.4.0_0+ssl\n\
+                      \x20 curl @8.5.0_0+ssl (active)\n\
+                      \x20 python312 @3.12.1_0+lto+optimizations (active)\n\
+                      \x20 zlib @1.3_0 (active) requested_variants=\n";
+
+        assert_eq!(
+            parse_installed(stdout),
+            vec![
+                ("curl".to_string(), "8.5.0_0".to_string()),
+                ("python312".to_string(), "3.12.1_0".to_string()),
+                ("zlib".to_string(), "1.3_0".to_string()),
+            ]
+        );
+        assert!(parse_installed("No ports are installed.\n").is_empty());
+    }
+
+    #[test]
+    fn macports_outdated_maps_newer_versions() {
+        let stdout = "The following installed ports are outdated:\n\
+                      curl                           8.5.0_0 < 8.6.0_0\n\
+                      python312                      3.12.1_0 < 3.12.2_0\n";
+
+        let updates = parse_outdated(stdout);
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates["curl"], "8.6.0_0");
+        assert!(parse_outdated("No installed ports are outdated.\n").is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{InventorySnapshot, PackageManager, UnknownManagerError};
+
+/// Expected package versions, keyed by `(manager, name)`, typically loaded from `bagpack.lock`.
+///
+/// The TOML form has one table per manager:
+///
+/// ```toml
+/// [brew]
+/// wget = "1.24.5"
+///
+/// [npm]
+/// typescript = "5.5.2"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub expected: BTreeMap<(PackageManager, String), String>,
+}
+
+/// Returned when a manifest cannot be parsed.
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("toml parse error: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    UnknownManager(#[from] UnknownManagerError),
+}
+
+impl Manifest {
+    /// Parses the TOML manifest format described on [`Manifest`].
+    pub fn from_toml(contents: &str) -> Result<Self, ManifestError> {
+        let tables: BTreeMap<String, BTreeMap<String, String>> = toml::from_str(contents)?;
+
+        let mut expected = BTreeMap::new();
+        for (manager, packages) in tables {
+            let manager: PackageManager = manager.parse()?;
+            for (name, version) in packages {
+                expected.insert((manager.clone(), name), version);
+            }
+        }
+        Ok(Self { expected })
+    }
+
+    /// Returns the managers the manifest lists packages for.
+    pub fn managers(&self) -> Vec<PackageManager> {
+        let managers: BTreeSet<PackageManager> = self
+            .expected
+            .keys()
+            .map(|(manager, _)| manager.clone())
+            .collect();
+        managers.into_iter().collect()
+    }
+}
+
+/// A package named by a manifest or found in a snapshot, with its version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub manager: PackageManager,
+    pub name: String,
+    pub version: String,
+}
+
+/// A package installed at a different version than the manifest expects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionMismatch {
+    pub manager: PackageManager,
+    pub name: String,
+    pub expected: String,
+    pub installed: String,
+}
+
+/// Drift between a snapshot and a [`Manifest`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestReport {
+    /// Manifest entries with no matching package, carrying the expected version.
+    pub missing: Vec<ManifestEntry>,
+    /// Installed packages the manifest does not list, carrying the installed version.
+    pub extra: Vec<ManifestEntry>,
+    /// Packages present in both at different versions.
+    pub mismatched: Vec<VersionMismatch>,
+}
+
+impl ManifestReport {
+    /// Returns whether the snapshot matches the manifest exactly.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// Compares `snapshot` against the versions `manifest` expects.
+///
+/// Only managers the manifest mentions are checked for extra packages, so a Homebrew-only
+/// manifest does not flag every npm package. Missing entries are sorted by manager and name;
+/// extra and mismatched entries follow snapshot order.
+pub fn check_against_manifest(snapshot: &InventorySnapshot, manifest: &Manifest) -> ManifestReport {
+    let managers = manifest.managers();
+    let installed: HashMap<(&PackageManager, &str), &str> = snapshot
+        .packages
+        .iter()
+        .map(|record| {
+            (
+                (&record.manager, record.name.as_str()),
+                record.current_version.as_str(),
+            )
+        })
+        .collect();
+
+    let mut report = ManifestReport::default();
+
+    for ((manager, name), version) in &manifest.expected {
+        if !installed.contains_key(&(manager, name.as_str())) {
+            report.missing.push(ManifestEntry {
+                manager: manager.clone(),
+                name: name.clone(),
+                version: version.clone(),
+            });
+        }
+    }
+
+    for record in &snapshot.packages {
+        match manifest
+            .expected
+            .get(&(record.manager.clone(), record.name.clone()))
+        {
+            Some(expected) if expected != &record.current_version => {
+                report.mismatched.push(VersionMismatch {
+                    manager: record.manager.clone(),
+                    name: record.name.clone(),
+                    expected: expected.clone(),
+                    installed: record.current_version.clone(),
+                });
+            }
+            Some(_) => {}
+            None if managers.contains(&record.manager) => {
+                report.extra.push(ManifestEntry {
+                    manager: record.manager.clone(),
+                    name: record.name.clone(),
+                    version: record.current_version.clone(),
+                });
+            }
+            None => {}
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_against_manifest, Manifest, ManifestEntry, ManifestError, VersionMismatch};
+    use crate::{demo_snapshot, PackageManager};
+
+    const LOCK: &str = r#"
+[brew]
+wget = "1.24.5"
+
+[npm]
+typescript = "5.5.2"
+
+[pip]
+requests = "2.32.3"
+"#;
+
+    #[test]
+    fn manifest_parses_toml_tables() {
+        let manifest = Manifest::from_toml(LOCK).unwrap();
+
+        assert_eq!(manifest.expected.len(), 3);
+        assert_eq!(
+            manifest.expected[&(PackageManager::Npm, "typescript".to_string())],
+            "5.5.2"
+        );
+        assert!(check_against_manifest(&demo_snapshot(), &manifest).is_clean());
+    }
+
+    #[test]
+    fn manifest_rejects_unknown_manager() {
+        assert!(matches!(
+            Manifest::from_toml("[apt]\ncurl = \"8.5.0\"\n"),
+            Err(ManifestError::UnknownManager(_))
+        ));
+    }
+
+    #[test]
+    fn missing_package_is_reported_with_expected_version() {
+        let manifest = Manifest::from_toml(&format!("{LOCK}jq = \"1.7.1\"\n")).unwrap();
+
+        let report = check_against_manifest(&demo_snapshot(), &manifest);
+
+        assert_eq!(
+            report.missing,
+            vec![ManifestEntry {
+                manager: PackageManager::Pip,
+                name: "jq".into(),
+                version: "1.7.1".into(),
+            }]
+        );
+        assert!(report.extra.is_empty() && report.mismatched.is_empty());
+    }
+
+    #[test]
+    fn extra_package_is_reported_for_listed_managers_only() {
+        let manifest = Manifest::from_toml("[brew]\njq = \"1.7.1\"\n").unwrap();
+
+        let report = check_against_manifest(&demo_snapshot(), &manifest);
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(
+            report.extra,
+            vec![ManifestEntry {
+                manager: PackageManager::Brew,
+                name: "wget".into(),
+                version: "1.24.5".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn version_mismatch_is_reported() {
+        let manifest = Manifest::from_toml(&LOCK.replace("5.5.2", "5.6.3")).unwrap();
+
+        let report = check_against_manifest(&demo_snapshot(), &manifest);
+
+        assert_eq!(
+            report.mismatched,
+            vec![VersionMismatch {
+                manager: PackageManager::Npm,
+                name: "typescript".into(),
+                expected: "5.6.3".into(),
+                installed: "5.5.2".into(),
+            }]
+        );
+        assert!(report.missing.is_empty() && report.extra.is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_mas(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "mas", &["list"], None::<&[i32]>)?;
+    ensure_success(&list_output, "mas list")?;
+
+    let installed: Vec<MasLine> = list_output.stdout.lines().filter_map(parse_line).collect();
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let outdated_output = run_command(options, "mas", &["outdated"], None::<&[i32]>)?;
+    ensure_success(&outdated_output, "mas outdated")?;
+
+    let latest_map: HashMap<String, String> = outdated_output
+        .stdout
+        .lines()
+        .filter_map(parse_line)
+        .filter_map(|line| line.latest_version.map(|latest| (line.id, latest)))
+        .collect();
+
+    let packages = installed
+        .into_iter()
+        .map(|line| {
+            let latest_version = latest_map.get(&line.id).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name: line.name,
+                current_version: line.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Mas,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: Some(line.id),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct MasLine {
+    id: String,
+    name: String,
+    version: String,
+    latest_version: Option<String>,
+}
+
+/// Parses `mas list` lines (`497799835  Xcode  (15.3)`) and `mas outdated` lines
+/// (`497799835 Xcode (15.2 -> 15.3)`).
+///
+/// App names may contain spaces and parentheses, so the version is taken from the final
+/// parenthesized group only.
+fn parse_line(line: &str) -> Option<MasLine> {
+    let line = line.trim();
+    let (id, rest) = line.split_once(char::is_whitespace)?;
+    if !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let rest = rest.trim().strip_suffix(')')?;
+    let open = rest.rfind('(')?;
+    let name = rest[..open].trim();
+    let versions = rest[open + 1..].trim();
+    if name.is_empty() || versions.is_empty() {
+        return None;
+    }
+
+    let (version, latest_version) = match versions.split_once("->") {
+        Some((current, latest)) => (current.trim(), Some(latest.trim().to_string())),
+        None => (versions, None),
+    };
+
+    Some(MasLine {
+        id: id.to_string(),
+        name: name.to_string(),
+        version: version.to_string(),
+        latest_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_line, MasLine};
+
+    #[test]
+    fn mas_list_line_extracts_id_name_and_version() {
+        assert_eq!(
+            parse_line("497799835  Xcode  (15.3)"),
+            Some(MasLine {
+                id: "497799835".into(),
+                name: "Xcode".into(),
+                version: "15.3".into(),
+                latest_version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn mas_list_line_keeps_parentheses_inside_names() {
+        let line = parse_line("904280696  Things 3 (Personal)  (3.20.1)").unwrap();
+
+        assert_eq!(line.name, "Things 3 (Personal)");
+        assert_eq!(line.version, "3.20.1");
+    }
+
+    #[test]
+    fn mas_outdated_line_includes_latest_version() {
+        let line = parse_line("497799835 Xcode (15.2 -> 15.3)").unwrap();
+
+        assert_eq!(line.version, "15.2");
+        assert_eq!(line.latest_version.as_deref(), Some("15.3"));
+    }
+
+    #[test]
+    fn mas_rejects_malformed_lines() {
+        assert_eq!(parse_line("No installed apps found"), None);
+        assert_eq!(parse_line("497799835 Xcode"), None);
+    }
+}
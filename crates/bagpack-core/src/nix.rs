@@ -0,0 +1,245 @@
+use serde::Deserialize;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus, UNKNOWN_VERSION,
+};
+
+pub(crate) fn collect_nix(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(
+        options,
+        "nix",
+        &["profile", "list", "--json"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&list_output, "nix profile list --json")?;
+
+    // nix profile has no cheap "what's newer" query, so status stays unknown.
+    let packages = parse_profile(&list_output.stdout)?
+        .into_iter()
+        .map(|(name, element)| {
+            let current_version = element
+                .store_paths
+                .first()
+                .and_then(|path| store_path_version(path))
+                .unwrap_or(UNKNOWN_VERSION)
+                .to_string();
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version: None,
+                installed_at: None,
+                status: PackageStatus::Unknown,
+                manager: PackageManager::Nix,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: element.original_url,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+#[derive(Debug, Deserialize)]
+struct NixProfile {
+    #[serde(default)]
+    elements: NixElements,
+}
+
+/// Profile manifest version 3 keys elements by name; older versions use a list.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NixElements {
+    Named(serde_json::Map<String, serde_json::Value>),
+    Listed(Vec<NixElement>),
+}
+
+impl Default for NixElements {
+    fn default() -> Self {
+        NixElements::Listed(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NixElement {
+    #[serde(default)]
+    attr_path: Option<String>,
+    #[serde(default)]
+    original_url: Option<String>,
+    #[serde(default)]
+    store_paths: Vec<String>,
+}
+
+fn parse_profile(stdout: &str) -> Result<Vec<(String, NixElement)>, CollectionError> {
+    let profile: NixProfile = serde_json::from_str(stdout)?;
+
+    let elements = match profile.elements {
+        NixElements::Named(entries) => entries
+            .into_iter()
+            .map(|(name, value)| Ok((name, serde_json::from_value(value)?)))
+            .collect::<Result<Vec<_>, serde_json::Error>>()?,
+        NixElements::Listed(entries) => entries
+            .into_iter()
+            .filter_map(|element| {
+                let name = element
+                    .attr_path
+                    .as_deref()
+                    .and_then(|path| path.rsplit('.').next())
+                    .or_else(|| {
+                        element
+                            .store_paths
+                            .first()
+                            .map(|path| parse_store_path(path).0)
+                    })?
+                    .to_string();
+                Some((name, element))
+            })
+            .collect(),
+    };
+
+    Ok(elements)
+}
+
+/// Output names nixpkgs appends to store paths of non-default outputs (`jq-1.7.1-bin`).
+const OUTPUT_SUFFIXES: &[&str] = &[
+    "bin", "out", "dev", "man", "doc", "lib", "info", "debug", "static",
+];
+
+/// Splits a store path into package name and version the way `builtins.parseDrvName` does,
+/// after dropping `/nix/store/<hash>-` and any output suffix: the version starts after the first
+/// `-` that is not followed by a letter.
+fn parse_store_path(path: &str) -> (&str, Option<&str>) {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let name = file_name
+        .split_once('-')
+        .map_or(file_name, |(_, name)| name);
+    let name = match name.rsplit_once('-') {
+        Some((rest, output)) if OUTPUT_SUFFIXES.contains(&output) => rest,
+        _ => name,
+    };
+
+    let split = name.char_indices().find(|&(index, c)| {
+        c == '-'
+            && name[index + 1..]
+                .chars()
+                .next()
+                .is_some_and(|next| !next.is_alphabetic())
+    });
+    match split {
+        Some((index, _)) if index + 1 < name.len() => (&name[..index], Some(&name[index + 1..])),
+        _ => (name, None),
+    }
+}
+
+fn store_path_version(path: &str) -> Option<&str> {
+    parse_store_path(path).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_profile, parse_store_path, store_path_version};
+
+    #[test]
+    fn nix_store_path_versions() {
+        assert_eq!(
+            store_path_version("/nix/store/0c2bzcpw1yfvz4vcl1qwwlw7y6p0xy7a-ripgrep-14.1.0"),
+            Some("14.1.0")
+        );
+        assert_eq!(
+            store_path_version(
+                "/nix/store/8xk4yl1r3n6kbyn05qhan7nbag7npymx-python3.11-black-24.2.0"
+            ),
+            Some("24.2.0")
+        );
+        assert_eq!(
+            store_path_version("/nix/store/a1b2c3d4e5f6g7h8i9j0k1l2m3n4o5p6-hello-world"),
+            None
+        );
+    }
+
+    #[test]
+    fn nix_store_paths_drop_output_suffixes() {
+        assert_eq!(
+            parse_store_path("/nix/store/x7f3lq8s1c4hffxwz6z5y4m8rqz9w0vb-jq-1.7.1-bin"),
+            ("jq", Some("1.7.1"))
+        );
+        assert_eq!(
+            parse_store_path("/nix/store/9w2l8a1k3m5n7p9r1t3v5x7z9b1d3f5h-openssl-3.0.13-dev"),
+            ("openssl", Some("3.0.13"))
+        );
+        assert_eq!(
+            parse_store_path("/nix/store/a1b2c3d4e5f6g7h8i9j0k1l2m3n4o5p6-hello-world"),
+            ("hello-world", None)
+        );
+    }
+
+    #[test]
+    fn nix_profile_v2_elements_without_attr_path_use_the_package_name() {
+        let stdout = r#"{
+            "elements": [
+                {"storePaths": ["/nix/store/0c2bzcpw1yfvz4vcl1qwwlw7y6p0xy7a-ripgrep-14.1.0"]}
+            ],
+            "version": 2
+        }"#;
+
+        assert_eq!(parse_profile(stdout).unwrap()[0].0, "ripgrep");
+    }
+
+    #[test]
+    fn nix_profile_v3_elements_are_keyed_by_name() {
+        let stdout = r#"{
+            "elements": {
+                "ripgrep": {
+                    "active": true,
+                    "attrPath": "legacyPackages.x86_64-linux.ripgrep",
+                    "originalUrl": "flake:nixpkgs",
+                    "priority": 5,
+                    "storePaths": ["/nix/store/0c2bzcpw1yfvz4vcl1qwwlw7y6p0xy7a-ripgrep-14.1.0"]
+                },
+                "hello-world": {
+                    "storePaths": ["/nix/store/a1b2c3d4e5f6g7h8i9j0k1l2m3n4o5p6-hello-world"]
+                }
+            },
+            "version": 3
+        }"#;
+
+        let elements = parse_profile(stdout).unwrap();
+
+        // Named elements come back sorted by name.
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].0, "hello-world");
+        assert_eq!(elements[1].0, "ripgrep");
+        assert_eq!(elements[1].1.original_url.as_deref(), Some("flake:nixpkgs"));
+    }
+
+    #[test]
+    fn nix_profile_v2_elements_use_attr_path() {
+        let stdout = r#"{
+            "elements": [
+                {
+                    "attrPath": "legacyPackages.aarch64-darwin.jq",
+                    "storePaths": ["/nix/store/x7f3lq8s1c4hffxwz6z5y4m8rqz9w0vb-jq-1.7.1-bin"]
+                }
+            ],
+            "version": 2
+        }"#;
+
+        let elements = parse_profile(stdout).unwrap();
+
+        assert_eq!(elements[0].0, "jq");
+        assert_eq!(
+            store_path_version(&elements[0].1.store_paths[0]),
+            Some("1.7.1")
+        );
+    }
+}
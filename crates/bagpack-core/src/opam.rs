@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_opam(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let switch_arg = options
+        .opam_switch
+        .as_ref()
+        .map(|switch| format!("--switch={switch}"));
+    let mut list_args = vec!["list", "--installed", "--columns=name,version", "--short"];
+    list_args.extend(switch_arg.as_deref());
+    let list_output = run_command(options, "opam", &list_args, None::<&[i32]>)?;
+    ensure_success(&list_output, "opam list --installed")?;
+
+    let installed = parse_installed(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut upgradable_args = vec!["list", "--upgradable", "--short"];
+    upgradable_args.extend(switch_arg.as_deref());
+    let upgradable_output = run_command(options, "opam", &upgradable_args, None::<&[i32]>)?;
+    ensure_success(&upgradable_output, "opam list --upgradable")?;
+
+    let upgradable = parse_upgradable(&upgradable_output.stdout);
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            // `opam list` only reports installed versions, so the target version is unknown.
+            let status = if upgradable.contains(name.as_str()) {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version: None,
+                installed_at: None,
+                status,
+                manager: PackageManager::Opam,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: options.opam_switch.clone(),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses `name version` rows, skipping `#` comment lines that opam prints without `--short`.
+fn parse_installed(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            match (columns.next(), columns.next()) {
+                (Some(name), Some(version)) => Some((name.to_string(), version.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Collects package names from `opam list --upgradable --short`, one per line.
+fn parse_upgradable(stdout: &str) -> HashSet<&str> {
+    stdout
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().next())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_installed, parse_upgradable};
+
+    #[test]
+    fn opam_list_parses_name_and_version() {
+        let stdout = "# Packages matching: installed\n\
+                      base-threads base\n\
+                      dune         3.14.0\n\
+                      ocaml        5.1.1\n";
+
+        assert_eq!(
+            parse_installed(stdout),
+            vec![
+                ("base-threads".to_string(), "base".to_string()),
+                ("dune".to_string(), "3.14.0".to_string()),
+                ("ocaml".to_string(), "5.1.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn opam_upgradable_lists_names() {
+        let upgradable = parse_upgradable("dune\nocamlformat\n");
+
+        assert!(upgradable.contains("dune"));
+        assert!(!upgradable.contains("ocaml"));
+        assert!(parse_upgradable("").is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_pacman(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "pacman", &["-Q"], None::<&[i32]>)?;
+    ensure_success(&list_output, "pacman -Q")?;
+
+    let installed = parse_installed(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // pacman exits 1 from `-Qu` when there is nothing to upgrade.
+    let upgrades_output = run_command(options, "pacman", &["-Qu"], Some(&[0, 1]))?;
+    let upgrades = parse_upgrades(&upgrades_output.stdout);
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = upgrades.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Pacman,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Parses `pacman -Q` lines shaped like `linux 6.9.7.arch1-1`.
+fn parse_installed(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(version)) => Some((name.to_string(), version.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Parses `pacman -Qu` lines shaped like `linux 6.9.7.arch1-1 -> 6.9.8.arch1-1`, mapping each
+/// name to its new version. Trailing markers such as `[ignored]` are dropped.
+fn parse_upgrades(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let _current = parts.next()?;
+            if parts.next()? != "->" {
+                return None;
+            }
+            let latest = parts.next()?;
+            Some((name.to_string(), latest.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_installed, parse_upgrades};
+
+    #[test]
+    fn pacman_query_lines_split_name_and_version() {
+        assert_eq!(
+            parse_installed("linux 6.9.7.arch1-1\npython-requests 2.32.3-1\n\n"),
+            vec![
+                ("linux".to_string(), "6.9.7.arch1-1".to_string()),
+                ("python-requests".to_string(), "2.32.3-1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn pacman_upgrade_lines_give_latest_version() {
+        let upgrades = parse_upgrades(
+            "linux 6.9.7.arch1-1 -> 6.9.8.arch1-1\nfirefox 127.0-1 -> 128.0-1 [ignored]\n",
+        );
+
+        assert_eq!(upgrades.len(), 2);
+        assert_eq!(upgrades["linux"], "6.9.8.arch1-1");
+        assert_eq!(upgrades["firefox"], "128.0-1");
+    }
+
+    #[test]
+    fn pacman_without_upgrades_is_empty() {
+        assert!(parse_upgrades("").is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+/// Matches `text` against a package-name pattern.
+///
+/// Patterns containing `*` (any run of characters) or `?` (any single character) must match the
+/// whole name; patterns without wildcards match as a prefix.
+pub(crate) fn matches(pattern: &str, text: &str) -> bool {
+    if pattern.contains(['*', '?']) {
+        matches_whole(pattern, text)
+    } else {
+        text.starts_with(pattern)
+    }
+}
+
+/// Matches `text` against a glob that must cover the whole name, so plain patterns only match
+/// the exact name.
+pub(crate) fn matches_whole(pattern: &str, text: &str) -> bool {
+    glob_match(
+        &pattern.chars().collect::<Vec<_>>(),
+        &text.chars().collect::<Vec<_>>(),
+    )
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Scores how well `name` matches a loosely typed `query`, from `0.0` (exclusive) to `1.0` for an
+/// exact match, or `None` when they are unrelated. Matching ignores ASCII case.
+///
+/// Names containing the query score above `0.75`, shorter names first; anything else is scored
+/// by edit distance below that, so `typscript` still finds `typescript`.
+pub(crate) fn fuzzy_score(query: &str, name: &str) -> Option<f32> {
+    let query = query.trim().to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    if query == name {
+        return Some(1.0);
+    }
+
+    let query: Vec<char> = query.chars().collect();
+    let name_chars: Vec<char> = name.chars().collect();
+    if name_chars
+        .windows(query.len())
+        .any(|window| window == query.as_slice())
+    {
+        // Strictly below an exact match, however long the query.
+        let coverage = query.len() as f32 / name_chars.len() as f32;
+        return Some(0.75 + 0.2 * coverage);
+    }
+
+    let longest = query.len().max(name_chars.len()) as f32;
+    let similarity = 1.0 - levenshtein(&query, &name_chars) as f32 / longest;
+    (similarity >= FUZZY_THRESHOLD).then_some(0.75 * similarity)
+}
+
+/// Minimum edit-distance similarity for a non-substring match to count.
+const FUZZY_THRESHOLD: f32 = 0.6;
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, &a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_score, matches, matches_whole};
+
+    #[test]
+    fn plain_patterns_match_prefixes() {
+        assert!(matches("@acme/", "@acme/internal-cli"));
+        assert!(!matches("@acme/", "acme"));
+    }
+
+    #[test]
+    fn wildcards_match_whole_names() {
+        assert!(matches("*-internal", "billing-internal"));
+        assert!(!matches("*-internal", "billing-internal-tools"));
+        assert!(matches("acme-*-cli", "acme-deploy-cli"));
+        assert!(matches("py?est", "pytest"));
+        assert!(!matches("py?est", "pyttest"));
+    }
+
+    #[test]
+    fn whole_name_patterns_do_not_match_prefixes() {
+        assert!(matches_whole("pip", "pip"));
+        assert!(!matches_whole("pip", "pipx"));
+        assert!(matches_whole("setuptools*", "setuptools-scm"));
+    }
+
+    #[test]
+    fn fuzzy_scores_rank_exact_then_substring_then_typos() {
+        let exact = fuzzy_score("typescript", "TypeScript").unwrap();
+        let substring = fuzzy_score("script", "typescript").unwrap();
+        let typo = fuzzy_score("typscript", "typescript").unwrap();
+
+        assert_eq!(exact, 1.0);
+        assert!(exact > substring && substring > typo);
+        assert_eq!(fuzzy_score("wget", "typescript"), None);
+        assert_eq!(fuzzy_score("  ", "typescript"), None);
+    }
+}
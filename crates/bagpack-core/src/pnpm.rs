@@ -0,0 +1,181 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_pnpm(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "pnpm", &["ls", "-g", "--json"], None::<&[i32]>)?;
+    ensure_success(&list_output, "pnpm ls -g --json")?;
+
+    let installed = parse_list(&list_output.stdout)?;
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Like npm, pnpm exits 1 from `outdated` when anything is outdated.
+    let outdated_output = run_command(
+        options,
+        "pnpm",
+        &["outdated", "-g", "--json"],
+        Some(&[0, 1]),
+    )?;
+
+    Ok(build_records(
+        installed,
+        &parse_outdated(&outdated_output.stdout)?,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct PnpmProject {
+    #[serde(default)]
+    dependencies: BTreeMap<String, PnpmPackage>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct PnpmPackage {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PnpmOutdated {
+    latest: Option<String>,
+}
+
+/// Parses `pnpm ls -g --json`, which, unlike npm's single tree, prints an array with one
+/// project per global directory.
+fn parse_list(stdout: &str) -> Result<Vec<(String, PnpmPackage)>, CollectionError> {
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let projects: Vec<PnpmProject> = serde_json::from_str(stdout)?;
+    Ok(projects
+        .into_iter()
+        .flat_map(|project| project.dependencies)
+        .collect())
+}
+
+/// Parses the `{"<name>": {"current", "latest", "wanted", ...}}` map from `pnpm outdated -g --json`.
+fn parse_outdated(stdout: &str) -> Result<HashMap<String, String>, CollectionError> {
+    if stdout.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let outdated: HashMap<String, PnpmOutdated> = serde_json::from_str(stdout)?;
+    Ok(outdated
+        .into_iter()
+        .filter_map(|(name, details)| details.latest.map(|latest| (name, latest)))
+        .collect())
+}
+
+fn build_records(
+    installed: Vec<(String, PnpmPackage)>,
+    outdated: &HashMap<String, String>,
+) -> Vec<PackageRecord> {
+    installed
+        .into_iter()
+        .map(|(name, pkg)| {
+            // `pnpm link -g` reports the target as the version, e.g. `link:../my-cli`.
+            let linked = pkg.version.starts_with("link:");
+            let (latest_version, status) = if linked {
+                (None, PackageStatus::Unknown)
+            } else {
+                match outdated.get(&name) {
+                    Some(latest) => (Some(latest.clone()), PackageStatus::Outdated),
+                    None => (None, PackageStatus::Current),
+                }
+            };
+
+            PackageRecord {
+                name,
+                current_version: pkg.version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Pnpm,
+                pinned: false,
+                dependency: false,
+                linked,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_list, parse_outdated};
+    use crate::PackageStatus;
+
+    const LIST: &str = r#"[
+  {
+    "path": "/home/dev/.local/share/pnpm/global/5",
+    "private": false,
+    "dependencies": {
+      "typescript": {
+        "from": "typescript",
+        "version": "5.5.2",
+        "resolved": "https://registry.npmjs.org/typescript/-/typescript-5.5.2.tgz",
+        "path": "/home/dev/.local/share/pnpm/global/5/node_modules/.pnpm/typescript@5.5.2/node_modules/typescript"
+      },
+      "my-cli": {
+        "from": "my-cli",
+        "version": "link:../../../../src/my-cli",
+        "path": "/home/dev/src/my-cli"
+      }
+    }
+  }
+]"#;
+
+    #[test]
+    fn pnpm_list_reads_dependencies_of_each_global_project() {
+        let installed = parse_list(LIST).unwrap();
+
+        let names: Vec<(&str, &str)> = installed
+            .iter()
+            .map(|(name, pkg)| (name.as_str(), pkg.version.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("my-cli", "link:../../../../src/my-cli"),
+                ("typescript", "5.5.2"),
+            ]
+        );
+        assert!(parse_list("[]").unwrap().is_empty());
+        assert!(parse_list("[{\"path\": \"/tmp/global/5\"}]")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn pnpm_outdated_marks_registry_packages_but_not_links() {
+        let outdated = parse_outdated(
+            r#"{
+  "typescript": {"current": "5.5.2", "latest": "5.6.3", "wanted": "5.5.2", "isDeprecated": false, "dependencyType": "dependencies"},
+  "my-cli": {"current": "link:../../../../src/my-cli", "latest": "1.0.0", "wanted": "link:../../../../src/my-cli", "isDeprecated": false, "dependencyType": "dependencies"}
+}"#,
+        )
+        .unwrap();
+
+        let records = build_records(parse_list(LIST).unwrap(), &outdated);
+
+        assert!(records[0].linked);
+        assert_eq!(records[0].status, PackageStatus::Unknown);
+        assert_eq!(records[0].latest_version, None);
+        assert!(!records[1].linked);
+        assert_eq!(records[1].status, PackageStatus::Outdated);
+        assert_eq!(records[1].latest_version.as_deref(), Some("5.6.3"));
+    }
+}
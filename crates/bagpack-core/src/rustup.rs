@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus, UNKNOWN_VERSION,
+};
+
+pub(crate) fn collect_rustup(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let toolchain_output = run_command(options, "rustup", &["toolchain", "list"], None::<&[i32]>)?;
+    ensure_success(&toolchain_output, "rustup toolchain list")?;
+
+    let toolchains = parse_toolchains(&toolchain_output.stdout);
+    if toolchains.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let component_output = run_command(
+        options,
+        "rustup",
+        &["component", "list", "--installed"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&component_output, "rustup component list --installed")?;
+
+    // Newer rustup releases exit with 100 when `check` finds pending updates. `check` needs the
+    // network, so when it fails (e.g. offline) the records are kept with an unknown status.
+    let checks = match run_command(options, "rustup", &["check"], Some(&[0, 100])) {
+        Ok(check_output) => parse_check(&check_output.stdout),
+        Err(CollectionError::Cancelled) => return Err(CollectionError::Cancelled),
+        Err(err) => {
+            log::warn!("rustup: update check failed: {err}");
+            HashMap::new()
+        }
+    };
+
+    Ok(build_records(
+        toolchains,
+        parse_components(&component_output.stdout),
+        &checks,
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Toolchain {
+    name: String,
+    is_default: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ToolchainCheck {
+    current_version: String,
+    latest_version: Option<String>,
+}
+
+/// Parses `rustup toolchain list`, e.g. `stable-aarch64-apple-darwin (active, default)`.
+fn parse_toolchains(stdout: &str) -> Vec<Toolchain> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("no installed toolchains"))
+        .filter_map(|line| {
+            let (name, annotations) = match line.split_once(' ') {
+                Some((name, rest)) => (name, rest),
+                None => (line, ""),
+            };
+            if name.is_empty() {
+                return None;
+            }
+
+            Some(Toolchain {
+                name: name.to_string(),
+                is_default: annotations.contains("default"),
+            })
+        })
+        .collect()
+}
+
+/// Parses `rustup component list --installed`, which prints one component per line.
+fn parse_components(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses `rustup check` lines shaped like
+/// `stable-x86_64-unknown-linux-gnu - Update available : 1.76.0 (07dca489a 2024-02-04) -> 1.77.0 (aedd173a2 2024-03-17)`
+/// or `nightly-x86_64-unknown-linux-gnu - Up to date : 1.79.0-nightly (0824b300e 2024-03-24)`.
+///
+/// The `rustup - ...` self-update line is skipped.
+fn parse_check(stdout: &str) -> HashMap<String, ToolchainCheck> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once(" - ")?;
+            if name == "rustup" {
+                return None;
+            }
+
+            let (_, versions) = rest.split_once(':')?;
+            let (current, latest) = match versions.split_once("->") {
+                Some((current, latest)) => (current, Some(latest)),
+                None => (versions, None),
+            };
+            let current_version = current.split_whitespace().next()?.to_string();
+            let latest_version = latest
+                .and_then(|latest| latest.split_whitespace().next())
+                .map(str::to_string);
+
+            Some((
+                name.to_string(),
+                ToolchainCheck {
+                    current_version,
+                    latest_version,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Turns toolchains and the default toolchain's components into records.
+///
+/// Components ship with their toolchain, so they inherit its version and status, and keep the
+/// toolchain name as `source` to tell them apart from toolchain records.
+fn build_records(
+    toolchains: Vec<Toolchain>,
+    components: Vec<String>,
+    checks: &HashMap<String, ToolchainCheck>,
+) -> Vec<PackageRecord> {
+    let record = |name: String, check: Option<&ToolchainCheck>, source: Option<String>| {
+        let (current_version, latest_version, status) = match check {
+            Some(check) => match &check.latest_version {
+                Some(latest) => (
+                    check.current_version.clone(),
+                    Some(latest.clone()),
+                    PackageStatus::Outdated,
+                ),
+                None => (check.current_version.clone(), None, PackageStatus::Current),
+            },
+            None => (UNKNOWN_VERSION.to_string(), None, PackageStatus::Unknown),
+        };
+
+        PackageRecord {
+            name,
+            current_version,
+            latest_version,
+            installed_at: None,
+            status,
+            manager: PackageManager::Rustup,
+            pinned: false,
+            dependency: false,
+            linked: false,
+            source,
+            homepage: None,
+            deprecated: None,
+            provenance: None,
+            download_size: None,
+        }
+    };
+
+    let default_toolchain = toolchains
+        .iter()
+        .find(|toolchain| toolchain.is_default)
+        .map(|toolchain| toolchain.name.clone());
+    let default_check = default_toolchain
+        .as_ref()
+        .and_then(|toolchain| checks.get(toolchain));
+
+    let mut packages: Vec<PackageRecord> = toolchains
+        .into_iter()
+        .map(|toolchain| {
+            let check = checks.get(&toolchain.name);
+            record(toolchain.name, check, None)
+        })
+        .collect();
+    packages.extend(
+        components
+            .into_iter()
+            .map(|component| record(component, default_check, default_toolchain.clone())),
+    );
+
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_check, parse_components, parse_toolchains, Toolchain};
+    use crate::PackageStatus;
+
+    #[test]
+    fn rustup_toolchain_list_marks_default() {
+        let stdout = "stable-x86_64-unknown-linux-gnu (active, default)\n\
+                      nightly-x86_64-unknown-linux-gnu\n";
+
+        assert_eq!(
+            parse_toolchains(stdout),
+            vec![
+                Toolchain {
+                    name: "stable-x86_64-unknown-linux-gnu".into(),
+                    is_default: true,
+                },
+                Toolchain {
+                    name: "nightly-x86_64-unknown-linux-gnu".into(),
+                    is_default: false,
+                },
+            ]
+        );
+        assert!(parse_toolchains("no installed toolchains\n").is_empty());
+    }
+
+    #[test]
+    fn rustup_check_feeds_stable_status() {
+        let toolchains = parse_toolchains(
+            "stable-x86_64-unknown-linux-gnu (default)\nnightly-x86_64-unknown-linux-gnu\n1.70.0-x86_64-unknown-linux-gnu\n",
+        );
+        let components =
+            parse_components("cargo-x86_64-unknown-linux-gnu\nrustfmt-x86_64-unknown-linux-gnu\n");
+        let checks = parse_check(
+            "stable-x86_64-unknown-linux-gnu - Update available : 1.76.0 (07dca489a 2024-02-04) -> 1.77.0 (aedd173a2 2024-03-17)\n\
+             nightly-x86_64-unknown-linux-gnu - Up to date : 1.79.0-nightly (0824b300e 2024-03-24)\n\
+             rustup - Up to date : 1.27.0\n",
+        );
+
+        let records = build_records(toolchains, components, &checks);
+
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].name, "stable-x86_64-unknown-linux-gnu");
+        assert_eq!(records[0].current_version, "1.76.0");
+        assert_eq!(records[0].latest_version.as_deref(), Some("1.77.0"));
+        assert_eq!(records[0].status, PackageStatus::Outdated);
+        assert_eq!(records[1].current_version, "1.79.0-nightly");
+        assert_eq!(records[1].status, PackageStatus::Current);
+        assert_eq!(records[2].status, PackageStatus::Unknown);
+        assert_eq!(records[3].name, "cargo-x86_64-unknown-linux-gnu");
+        assert_eq!(records[3].status, PackageStatus::Outdated);
+        assert_eq!(records[0].source, None);
+        assert_eq!(
+            records[3].source.as_deref(),
+            Some("stable-x86_64-unknown-linux-gnu")
+        );
+    }
+}
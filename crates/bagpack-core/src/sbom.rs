@@ -0,0 +1,310 @@
+use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::{InventorySnapshot, PackageManager, PackageRecord, UNKNOWN_VERSION};
+
+/// Renders the snapshot as a minimal CycloneDX 1.5 JSON BOM with one `library` component per
+/// record, each identified by its [package URL](https://github.com/package-url/purl-spec).
+///
+/// Purls repeat when the same package is installed through several managers or environments
+/// (npm and pnpm, or two gem homes), so each `bom-ref` is the purl plus the record index.
+pub fn to_cyclonedx(snapshot: &InventorySnapshot) -> Value {
+    let components: Vec<Value> = snapshot
+        .packages
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let purl = purl(record);
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": format!("{purl}#{index}"),
+                "name": record.name,
+                "purl": purl,
+            });
+            if let Some(version) = known_version(record) {
+                component["version"] = version.into();
+            }
+            component
+        })
+        .collect();
+
+    let mut metadata = json!({
+        "tools": {
+            "components": [{
+                "type": "application",
+                "name": "bagpack",
+                "version": env!("CARGO_PKG_VERSION"),
+            }],
+        },
+    });
+    if let Some(generated_at) = &snapshot.generated_at {
+        metadata["timestamp"] = generated_at.clone().into();
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": metadata,
+        "components": components,
+    })
+}
+
+/// Renders the snapshot as an SPDX 2.3 JSON document that describes one package per record.
+///
+/// The document namespace and creation time come from `generated_at`, falling back to the
+/// current time for snapshots without one. Package ids are `SPDXRef-Package-<name>-<index>`, so
+/// records sharing a name across managers stay distinct.
+pub fn to_spdx(snapshot: &InventorySnapshot) -> Value {
+    let created = snapshot.generated_at.clone().unwrap_or_else(|| {
+        OffsetDateTime::now_utc()
+            .replace_nanosecond(0)
+            .ok()
+            .and_then(|now| now.format(&Rfc3339).ok())
+            .unwrap_or_default()
+    });
+
+    let packages: Vec<Value> = snapshot
+        .packages
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            json!({
+                "SPDXID": spdx_id(index, record),
+                "name": record.name,
+                "versionInfo": known_version(record).unwrap_or("NOASSERTION"),
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": purl(record),
+                }],
+            })
+        })
+        .collect();
+    let relationships: Vec<Value> = snapshot
+        .packages
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": spdx_id(index, record),
+            })
+        })
+        .collect();
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": "bagpack-inventory",
+        "documentNamespace": format!("https://spdx.org/spdxdocs/bagpack-inventory-{created}"),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: bagpack-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// SPDX ids only allow letters, digits, `.`, and `-`; the index keeps them unique.
+fn spdx_id(index: usize, record: &PackageRecord) -> String {
+    let name: String = record
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("SPDXRef-Package-{name}-{index}")
+}
+
+fn known_version(record: &PackageRecord) -> Option<&str> {
+    (record.current_version != UNKNOWN_VERSION).then_some(record.current_version.as_str())
+}
+
+/// Builds the package URL for `record`.
+///
+/// Managers with a registered purl type use it (npm and pnpm share `npm`, pip maps to `pypi`,
+/// distro managers to `alpm`, `apk`, or `rpm` with the distro as namespace); Homebrew uses the
+/// community `brew` type and everything else falls back to `generic`.
+fn purl(record: &PackageRecord) -> String {
+    let (kind, namespace) = match &record.manager {
+        PackageManager::Brew => ("brew", None),
+        PackageManager::Npm | PackageManager::Pnpm => ("npm", None),
+        PackageManager::Pip => ("pypi", None),
+        PackageManager::Gem => ("gem", None),
+        PackageManager::Composer => ("composer", None),
+        PackageManager::Cabal => ("hackage", None),
+        PackageManager::Hex => ("hex", None),
+        PackageManager::DotnetTool => ("nuget", None),
+        PackageManager::Choco => ("chocolatey", None),
+        PackageManager::LuaRocks => ("luarocks", None),
+        PackageManager::Opam => ("opam", None),
+        PackageManager::Pacman => ("alpm", Some("arch")),
+        PackageManager::Apk => ("apk", Some("alpine")),
+        PackageManager::Dnf => ("rpm", Some("fedora")),
+        PackageManager::Zypper => ("rpm", Some("opensuse")),
+        _ => ("generic", None),
+    };
+
+    let name = match record.manager {
+        // PyPI names are case-insensitive and treat `_` like `-`; purls use the normalized form.
+        PackageManager::Pip => record.name.to_lowercase().replace('_', "-"),
+        _ => record.name.clone(),
+    };
+    // npm scopes and Composer vendors are purl namespaces rather than part of the name.
+    let (namespace, name) = match (kind, name.split_once('/')) {
+        ("npm" | "composer", Some((scope, rest))) => (Some(scope.to_string()), rest.to_string()),
+        _ => (namespace.map(String::from), name),
+    };
+
+    let mut purl = format!("pkg:{kind}/");
+    if let Some(namespace) = namespace {
+        purl.push_str(&purl_encode(&namespace));
+        purl.push('/');
+    }
+    purl.push_str(&purl_encode(&name));
+    if let Some(version) = known_version(record) {
+        purl.push('@');
+        purl.push_str(&purl_encode(version));
+    }
+    purl
+}
+
+/// Percent-encodes everything but unreserved characters, as purl components require.
+fn purl_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b".-_~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{purl, to_cyclonedx, to_spdx};
+    use crate::{demo_snapshot, InventorySnapshot, PackageManager, PackageRecord};
+
+    #[test]
+    fn cyclonedx_has_one_component_per_record() {
+        let bom = to_cyclonedx(&demo_snapshot());
+
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], "1.5");
+        assert_eq!(bom["metadata"]["timestamp"], "2025-10-05T12:34:56Z");
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0]["name"], "wget");
+        assert_eq!(components[0]["version"], "1.24.5");
+        let purls: Vec<&str> = components
+            .iter()
+            .map(|component| component["purl"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            purls,
+            vec![
+                "pkg:brew/wget@1.24.5",
+                "pkg:npm/typescript@5.5.2",
+                "pkg:pypi/requests@2.32.3",
+            ]
+        );
+    }
+
+    #[test]
+    fn cyclonedx_bom_refs_are_unique_for_shared_purls() {
+        let mut snapshot = InventorySnapshot::default();
+        for manager in [PackageManager::Npm, PackageManager::Pnpm] {
+            snapshot.push(
+                PackageRecord::builder("typescript", manager)
+                    .current_version("5.6.3")
+                    .build(),
+            );
+        }
+
+        let bom = to_cyclonedx(&snapshot);
+
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components[0]["purl"], components[1]["purl"]);
+        let refs: HashSet<&str> = components
+            .iter()
+            .map(|component| component["bom-ref"].as_str().unwrap())
+            .collect();
+        assert_eq!(refs.len(), 2);
+    }
+
+    #[test]
+    fn spdx_has_uniquely_identified_packages() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages.push(
+            PackageRecord::builder("typescript", PackageManager::Pnpm)
+                .current_version("5.6.3")
+                .build(),
+        );
+        let document = to_spdx(&snapshot);
+
+        assert_eq!(document["spdxVersion"], "SPDX-2.3");
+        assert_eq!(
+            document["documentNamespace"],
+            "https://spdx.org/spdxdocs/bagpack-inventory-2025-10-05T12:34:56Z"
+        );
+        let packages = document["packages"].as_array().unwrap();
+        assert_eq!(packages.len(), 4);
+        let ids: HashSet<&str> = packages
+            .iter()
+            .map(|package| package["SPDXID"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.iter().all(|id| id.starts_with("SPDXRef-Package-")));
+        assert_eq!(document["relationships"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn purl_splits_namespaces_and_encodes_names() {
+        let purl_of = |name: &str, manager| {
+            purl(
+                &PackageRecord::builder(name, manager)
+                    .current_version("1.0.0")
+                    .build(),
+            )
+        };
+
+        assert_eq!(
+            purl_of("@types/node", PackageManager::Pnpm),
+            "pkg:npm/%40types/node@1.0.0"
+        );
+        assert_eq!(
+            purl_of("laravel/installer", PackageManager::Composer),
+            "pkg:composer/laravel/installer@1.0.0"
+        );
+        assert_eq!(
+            purl_of("Typing_Extensions", PackageManager::Pip),
+            "pkg:pypi/typing-extensions@1.0.0"
+        );
+        assert_eq!(
+            purl_of("bash", PackageManager::Pacman),
+            "pkg:alpm/arch/bash@1.0.0"
+        );
+        assert_eq!(
+            purl(
+                &PackageRecord::builder("acme tool", PackageManager::Other("acme".into())).build()
+            ),
+            "pkg:generic/acme%20tool"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+
+use crate::{InventorySnapshot, PackageManager};
+
+/// JSON Schema describing the serialized [`InventorySnapshot`], including every enum value.
+pub fn json_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(InventorySnapshot))
+        .expect("generated schema serializes to JSON")
+}
+
+/// Written by hand because [`PackageManager::Other`] serializes as a bare string, which the
+/// derive would describe as an `{"other": ...}` object.
+impl JsonSchema for PackageManager {
+    fn schema_name() -> String {
+        "PackageManager".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let built_in = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(
+                PackageManager::ALL
+                    .iter()
+                    .map(|manager| manager.as_str().into())
+                    .collect(),
+            ),
+            ..SchemaObject::default()
+        };
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![built_in.into(), String::json_schema(gen)]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_schema;
+    use crate::demo_snapshot;
+
+    #[test]
+    fn schema_validates_demo_snapshot() {
+        let schema = jsonschema::JSONSchema::compile(&json_schema()).unwrap();
+        let instance = serde_json::to_value(demo_snapshot()).unwrap();
+
+        assert!(schema.is_valid(&instance));
+    }
+
+    #[test]
+    fn schema_accepts_custom_manager_names_but_not_other_types() {
+        let schema = jsonschema::JSONSchema::compile(&json_schema()).unwrap();
+        let mut instance = serde_json::to_value(demo_snapshot()).unwrap();
+        instance["packages"][0]["manager"] = "apt-get".into();
+        assert!(schema.is_valid(&instance));
+
+        instance["packages"][0]["manager"] = serde_json::json!({ "other": "apt-get" });
+        assert!(!schema.is_valid(&instance));
+    }
+}
@@ -0,0 +1,179 @@
+use thiserror::Error;
+
+use crate::CollectionSummary;
+
+/// Destination that collection summaries are pushed to, such as [`watch`](crate::watch)
+/// publishing after every scan.
+pub trait SnapshotSink {
+    fn publish(&self, summary: &CollectionSummary) -> Result<(), SinkError>;
+}
+
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error("failed to serialize summary: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to reach sink: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unsupported sink url: {0}")]
+    InvalidUrl(String),
+    #[error("sink responded with status {0}")]
+    Status(u16),
+}
+
+#[cfg(feature = "network")]
+pub use http::HttpSink;
+
+#[cfg(feature = "network")]
+mod http {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use super::{SinkError, SnapshotSink};
+    use crate::CollectionSummary;
+
+    /// Posts each summary as JSON to a plain `http://` endpoint.
+    ///
+    /// There is no TLS support, so point it at an internal collector or a local proxy.
+    #[derive(Debug, Clone)]
+    pub struct HttpSink {
+        host: String,
+        port: u16,
+        path: String,
+        timeout: Duration,
+    }
+
+    impl HttpSink {
+        /// Parses `url` of the form `http://host[:port][/path]`.
+        pub fn new(url: &str) -> Result<Self, SinkError> {
+            let invalid = || SinkError::InvalidUrl(url.to_string());
+            let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+            let (authority, path) = match rest.find('/') {
+                Some(slash) => (&rest[..slash], &rest[slash..]),
+                None => (rest, "/"),
+            };
+            let (host, port) = match authority.rsplit_once(':') {
+                Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+                None => (authority, 80),
+            };
+            if host.is_empty() {
+                return Err(invalid());
+            }
+
+            Ok(Self {
+                host: host.to_string(),
+                port,
+                path: path.to_string(),
+                timeout: Duration::from_secs(10),
+            })
+        }
+
+        /// Sets the connect, read, and write timeout (10 seconds by default).
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = timeout;
+            self
+        }
+    }
+
+    impl SnapshotSink for HttpSink {
+        fn publish(&self, summary: &CollectionSummary) -> Result<(), SinkError> {
+            let body = serde_json::to_vec(summary)?;
+
+            let address = (self.host.as_str(), self.port);
+            let mut stream = std::net::ToSocketAddrs::to_socket_addrs(&address)?
+                .find_map(|addr| TcpStream::connect_timeout(&addr, self.timeout).ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::ConnectionRefused,
+                        format!("could not connect to {}:{}", self.host, self.port),
+                    )
+                })?;
+            stream.set_read_timeout(Some(self.timeout))?;
+            stream.set_write_timeout(Some(self.timeout))?;
+
+            write!(
+                stream,
+                "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n",
+                self.path,
+                self.host,
+                body.len()
+            )?;
+            stream.write_all(&body)?;
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response)?;
+            let status = response
+                .split_whitespace()
+                .nth(1)
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed HTTP response")
+                })?;
+            if !(200..300).contains(&status) {
+                return Err(SinkError::Status(status));
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+
+        use super::HttpSink;
+        use crate::{demo_snapshot, CollectionSummary, SinkError, SnapshotSink};
+
+        #[test]
+        fn http_sink_rejects_non_http_urls() {
+            assert!(matches!(
+                HttpSink::new("https://example.com/hook"),
+                Err(SinkError::InvalidUrl(_))
+            ));
+            assert!(matches!(
+                HttpSink::new("http://:8080/hook"),
+                Err(SinkError::InvalidUrl(_))
+            ));
+        }
+
+        #[test]
+        fn http_sink_posts_summary_json() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let server = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut head = Vec::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    head.push(line);
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                    .unwrap();
+                (head, body)
+            });
+
+            let sink = HttpSink::new(&format!("http://127.0.0.1:{port}/hooks/bagpack")).unwrap();
+            sink.publish(&CollectionSummary::new(demo_snapshot()))
+                .unwrap();
+
+            let (head, body) = server.join().unwrap();
+            assert_eq!(head[0], "POST /hooks/bagpack HTTP/1.1\r\n");
+            let published: CollectionSummary = serde_json::from_slice(&body).unwrap();
+            assert_eq!(published.snapshot, demo_snapshot());
+        }
+    }
+}
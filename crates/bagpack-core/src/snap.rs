@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_snap(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "snap", &["list"], None::<&[i32]>)?;
+    ensure_success(&list_output, "snap list")?;
+
+    let installed = parse_table(&list_output.stdout);
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let refresh_output = run_command(options, "snap", &["refresh", "--list"], None::<&[i32]>)?;
+    ensure_success(&refresh_output, "snap refresh --list")?;
+
+    let updates: HashMap<String, String> =
+        parse_table(&refresh_output.stdout).into_iter().collect();
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = updates.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Snap,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Extracts `(name, version)` pairs from the column layout shared by `snap list` and
+/// `snap refresh --list`.
+///
+/// The first line is the `Name Version Rev ...` header. The `Rev` column is the store revision,
+/// not the version, so only the first two columns are read. `refresh --list` reports
+/// "All snaps up to date." instead of a table when nothing is pending, which yields no rows.
+fn parse_table(stdout: &str) -> Vec<(String, String)> {
+    let mut lines = stdout.lines().filter(|line| !line.trim().is_empty());
+
+    match lines.next() {
+        Some(header) if header.split_whitespace().next() == Some("Name") => {}
+        _ => return Vec::new(),
+    }
+
+    lines
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            match (columns.next(), columns.next()) {
+                (Some(name), Some(version)) => Some((name.to_string(), version.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_table;
+
+    #[test]
+    fn snap_list_reads_version_not_revision() {
+        let stdout = "Name      Version         Rev    Tracking       Publisher   Notes\n\
+                      core22    20240111        1122   latest/stable  canonical✓  base\n\
+                      firefox   124.0.2-1       4090   latest/stable  mozilla✓    -\n";
+
+        assert_eq!(
+            parse_table(stdout),
+            vec![
+                ("core22".to_string(), "20240111".to_string()),
+                ("firefox".to_string(), "124.0.2-1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn snap_refresh_up_to_date_yields_no_updates() {
+        assert!(parse_table("All snaps up to date.\n").is_empty());
+        assert!(parse_table("").is_empty());
+    }
+}
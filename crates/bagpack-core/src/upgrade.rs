@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+/// Outcome of [`upgrade_manager`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeResult {
+    pub manager: PackageManager,
+    /// Whether every upgrade command ran and exited successfully.
+    pub success: bool,
+    /// Stdout and stderr of each command in run order, followed by the error that stopped the
+    /// upgrade, if any.
+    pub output: String,
+}
+
+/// Upgrades every package of `manager` with its bulk upgrade command, such as `brew upgrade`.
+///
+/// Commands run with the environment and exit code overrides from `options`. Managers that need
+/// root for upgrades (pacman, dnf, apk, ...) or lack a bulk upgrade command are rejected with
+/// [`CollectionError::UpgradeUnsupported`] in the output.
+pub fn upgrade_manager(
+    manager: PackageManager,
+    options: &CollectInventoryOptions,
+) -> UpgradeResult {
+    let mut output = String::new();
+    let result = upgrade_plan(manager.clone(), options).and_then(|plan| {
+        for argv in plan {
+            let (program, args) = argv.split_first().expect("upgrade commands are not empty");
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let command_output = run_command(options, program, &args, None::<&[i32]>)?;
+            output.push_str(&command_output.stdout);
+            output.push_str(&command_output.stderr);
+            ensure_success(&command_output, &argv.join(" "))?;
+        }
+        Ok(())
+    });
+
+    if let Err(err) = &result {
+        log::warn!("{}: upgrade failed: {err}", manager.as_str());
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&err.to_string());
+    }
+
+    UpgradeResult {
+        manager,
+        success: result.is_ok(),
+        output,
+    }
+}
+
+/// Returns the argv of each command [`upgrade_manager`] would run, without upgrading anything.
+///
+/// pip has no "upgrade everything" command, so its outdated packages are listed first and
+/// passed to `pip install -U`; nothing is planned when none are outdated.
+pub fn upgrade_plan(
+    manager: PackageManager,
+    options: &CollectInventoryOptions,
+) -> Result<Vec<Vec<String>>, CollectionError> {
+    let argv: &[&str] = match manager {
+        PackageManager::Brew => &["brew", "upgrade"],
+        PackageManager::Npm => &["npm", "update", "-g"],
+        PackageManager::Pip => return pip_upgrade_plan(options),
+        PackageManager::Flatpak => &["flatpak", "update", "-y"],
+        PackageManager::Snap => &["snap", "refresh"],
+        PackageManager::Rustup => &["rustup", "update"],
+        PackageManager::Mas => &["mas", "upgrade"],
+        PackageManager::Composer => &["composer", "global", "update"],
+        PackageManager::Choco => &["choco", "upgrade", "all", "-y"],
+        PackageManager::Gem => &["gem", "update"],
+        _ => return Err(CollectionError::UpgradeUnsupported(manager)),
+    };
+
+    Ok(vec![argv.iter().map(|arg| arg.to_string()).collect()])
+}
+
+fn pip_upgrade_plan(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<Vec<String>>, CollectionError> {
+    let outdated_output = run_command(
+        options,
+        "pip",
+        &["list", "--outdated", "--format=json"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&outdated_output, "pip list --outdated --format=json")?;
+
+    #[derive(Debug, Deserialize)]
+    struct PipOutdated {
+        name: String,
+    }
+
+    if outdated_output.stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let outdated: Vec<PipOutdated> = serde_json::from_str(&outdated_output.stdout)?;
+    if outdated.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut argv = package_upgrade_prefix(&PackageManager::Pip)
+        .expect("pip upgrades packages by name")
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    argv.extend(outdated.into_iter().map(|pkg| pkg.name));
+    Ok(vec![argv])
+}
+
+/// Returns the argv upgrading only `record`, for [`PackageRecord::upgrade_command`].
+pub(crate) fn package_upgrade_command(record: &PackageRecord) -> Option<Vec<String>> {
+    if record.status != PackageStatus::Outdated || record.linked {
+        return None;
+    }
+    // mas upgrades by App Store id rather than by display name, and rustup components are
+    // updated through the toolchain they belong to.
+    let target = match record.manager {
+        PackageManager::Mas => record.source.as_deref()?,
+        PackageManager::Rustup => record.source.as_deref().unwrap_or(&record.name),
+        _ => record.name.as_str(),
+    };
+
+    let mut argv: Vec<String> = package_upgrade_prefix(&record.manager)?
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    argv.push(target.to_string());
+    Some(argv)
+}
+
+/// The command that upgrades packages named after it, for the managers [`upgrade_plan`]
+/// supports.
+fn package_upgrade_prefix(manager: &PackageManager) -> Option<&'static [&'static str]> {
+    let prefix: &[&str] = match manager {
+        PackageManager::Brew => &["brew", "upgrade"],
+        PackageManager::Npm => &["npm", "update", "-g"],
+        PackageManager::Pip => &["pip", "install", "-U"],
+        PackageManager::Flatpak => &["flatpak", "update", "-y"],
+        PackageManager::Snap => &["snap", "refresh"],
+        PackageManager::Rustup => &["rustup", "update"],
+        PackageManager::Mas => &["mas", "upgrade"],
+        PackageManager::Composer => &["composer", "global", "update"],
+        PackageManager::Choco => &["choco", "upgrade", "-y"],
+        PackageManager::Gem => &["gem", "update"],
+        _ => return None,
+    };
+    Some(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PackageManager, PackageRecord, PackageStatus};
+
+    fn outdated(name: &str, manager: PackageManager) -> PackageRecord {
+        PackageRecord::builder(name, manager)
+            .current_version("1.0.0")
+            .latest_version("2.0.0")
+            .status(PackageStatus::Outdated)
+            .build()
+    }
+
+    #[test]
+    fn upgrade_command_targets_a_single_package() {
+        let cases = [
+            (PackageManager::Brew, "wget", "brew upgrade wget"),
+            (
+                PackageManager::Npm,
+                "typescript",
+                "npm update -g typescript",
+            ),
+            (PackageManager::Pip, "requests", "pip install -U requests"),
+            (
+                PackageManager::Flatpak,
+                "org.gimp.GIMP",
+                "flatpak update -y org.gimp.GIMP",
+            ),
+            (PackageManager::Snap, "firefox", "snap refresh firefox"),
+            (PackageManager::Rustup, "stable", "rustup update stable"),
+            (
+                PackageManager::Composer,
+                "laravel/installer",
+                "composer global update laravel/installer",
+            ),
+            (PackageManager::Choco, "git", "choco upgrade -y git"),
+            (PackageManager::Gem, "rails", "gem update rails"),
+        ];
+        for (manager, name, expected) in cases {
+            assert_eq!(
+                outdated(name, manager)
+                    .upgrade_command()
+                    .map(|argv| argv.join(" ")),
+                Some(expected.to_string())
+            );
+        }
+
+        let cargo = PackageRecord {
+            source: Some("stable-x86_64-unknown-linux-gnu".into()),
+            ..outdated("cargo-x86_64-unknown-linux-gnu", PackageManager::Rustup)
+        };
+        assert_eq!(
+            cargo.upgrade_command().unwrap(),
+            vec!["rustup", "update", "stable-x86_64-unknown-linux-gnu"]
+        );
+
+        let xcode = PackageRecord {
+            source: Some("497799835".into()),
+            ..outdated("Xcode", PackageManager::Mas)
+        };
+        assert_eq!(
+            xcode.upgrade_command().unwrap(),
+            vec!["mas", "upgrade", "497799835"]
+        );
+    }
+
+    #[test]
+    fn upgrade_command_is_none_unless_outdated_and_supported() {
+        let current = PackageRecord {
+            status: PackageStatus::Current,
+            ..outdated("wget", PackageManager::Brew)
+        };
+        assert_eq!(current.upgrade_command(), None);
+        assert_eq!(
+            outdated("bash", PackageManager::Pacman).upgrade_command(),
+            None
+        );
+        assert_eq!(
+            outdated("Xcode", PackageManager::Mas).upgrade_command(),
+            None
+        );
+    }
+}
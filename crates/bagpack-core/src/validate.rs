@@ -0,0 +1,140 @@
+use thiserror::Error;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::{InventorySnapshot, PackageStatus};
+
+/// An internal inconsistency found by [`InventorySnapshot::validate`].
+///
+/// Package-level issues carry the record's index in [`InventorySnapshot::packages`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationIssue {
+    #[error("generated_at is not an RFC 3339 timestamp: {0}")]
+    InvalidGeneratedAt(String),
+    #[error("package #{index} has an empty name")]
+    EmptyName { index: usize },
+    #[error("package #{index} ({name}) is outdated but has no latest_version")]
+    OutdatedWithoutLatest { index: usize, name: String },
+    #[error("package #{index} ({name}) has an installed_at that is not RFC 3339: {value}")]
+    InvalidInstalledAt {
+        index: usize,
+        name: String,
+        value: String,
+    },
+}
+
+pub(crate) fn validate(snapshot: &InventorySnapshot) -> Result<(), Vec<ValidationIssue>> {
+    let is_timestamp = |value: &str| OffsetDateTime::parse(value, &Rfc3339).is_ok();
+    let mut issues = Vec::new();
+
+    if let Some(generated_at) = &snapshot.generated_at {
+        if !is_timestamp(generated_at) {
+            issues.push(ValidationIssue::InvalidGeneratedAt(generated_at.clone()));
+        }
+    }
+
+    for (index, record) in snapshot.packages.iter().enumerate() {
+        if record.name.trim().is_empty() {
+            issues.push(ValidationIssue::EmptyName { index });
+        }
+        if record.status == PackageStatus::Outdated && record.latest_version.is_none() {
+            issues.push(ValidationIssue::OutdatedWithoutLatest {
+                index,
+                name: record.name.clone(),
+            });
+        }
+        if let Some(installed_at) = &record.installed_at {
+            if !is_timestamp(installed_at) {
+                issues.push(ValidationIssue::InvalidInstalledAt {
+                    index,
+                    name: record.name.clone(),
+                    value: installed_at.clone(),
+                });
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationIssue;
+    use crate::{demo_snapshot, PackageStatus};
+
+    #[test]
+    fn demo_snapshot_is_valid() {
+        assert_eq!(demo_snapshot().validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_generated_at_is_reported() {
+        let mut snapshot = demo_snapshot();
+        snapshot.generated_at = Some("yesterday".into());
+
+        assert_eq!(
+            snapshot.validate(),
+            Err(vec![ValidationIssue::InvalidGeneratedAt(
+                "yesterday".into()
+            )])
+        );
+    }
+
+    #[test]
+    fn empty_name_is_reported() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages[1].name = " ".into();
+
+        assert_eq!(
+            snapshot.validate(),
+            Err(vec![ValidationIssue::EmptyName { index: 1 }])
+        );
+    }
+
+    #[test]
+    fn outdated_without_latest_is_reported() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages[0].latest_version = None;
+
+        assert_eq!(
+            snapshot.validate(),
+            Err(vec![ValidationIssue::OutdatedWithoutLatest {
+                index: 0,
+                name: "wget".into(),
+            }])
+        );
+    }
+
+    #[test]
+    fn invalid_installed_at_is_reported() {
+        let mut snapshot = demo_snapshot();
+        snapshot.packages[0].installed_at = Some("2024-09-17".into());
+
+        assert_eq!(
+            snapshot.validate(),
+            Err(vec![ValidationIssue::InvalidInstalledAt {
+                index: 0,
+                name: "wget".into(),
+                value: "2024-09-17".into(),
+            }])
+        );
+    }
+
+    #[test]
+    fn every_issue_is_collected() {
+        let mut snapshot = demo_snapshot();
+        snapshot.generated_at = Some("not a date".into());
+        snapshot.packages[0].latest_version = None;
+        snapshot.packages[2].name.clear();
+        snapshot.packages[2].status = PackageStatus::Outdated;
+        snapshot.packages[2].latest_version = None;
+
+        let issues = snapshot.validate().unwrap_err();
+
+        assert_eq!(issues.len(), 4, "{issues:?}");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus,
+};
+
+pub(crate) fn collect_vcpkg(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    let list_output = run_command(options, "vcpkg", &["list", "--x-json"], None::<&[i32]>)?;
+    ensure_success(&list_output, "vcpkg list --x-json")?;
+
+    Ok(build_records(parse_ports(&list_output.stdout)?))
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct VcpkgPort {
+    package_name: String,
+    triplet: String,
+    version: String,
+    #[serde(default)]
+    port_version: u32,
+}
+
+/// Parses the `{"<port>:<triplet>": {...}}` document printed by `vcpkg list --x-json`.
+///
+/// Ports installed with extra features may appear once per feature, so entries are collapsed
+/// to one per port and triplet.
+fn parse_ports(stdout: &str) -> Result<Vec<VcpkgPort>, CollectionError> {
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let listing: BTreeMap<String, VcpkgPort> = serde_json::from_str(stdout)?;
+    let mut ports: Vec<VcpkgPort> = Vec::with_capacity(listing.len());
+    for port in listing.into_values() {
+        if !ports
+            .iter()
+            .any(|seen| seen.package_name == port.package_name && seen.triplet == port.triplet)
+        {
+            ports.push(port);
+        }
+    }
+    Ok(ports)
+}
+
+/// Turns ports into records tagged with their triplet.
+///
+/// A non-zero port version is appended as `#N`, matching how vcpkg prints versions.
+fn build_records(ports: Vec<VcpkgPort>) -> Vec<PackageRecord> {
+    ports
+        .into_iter()
+        .map(|port| {
+            let current_version = if port.port_version == 0 {
+                port.version
+            } else {
+                format!("{}#{}", port.version, port.port_version)
+            };
+
+            PackageRecord {
+                name: port.package_name,
+                current_version,
+                latest_version: None,
+                installed_at: None,
+                status: PackageStatus::Unknown,
+                manager: PackageManager::Vcpkg,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: Some(port.triplet),
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_records, parse_ports};
+    use crate::PackageStatus;
+
+    #[test]
+    fn vcpkg_json_list_yields_one_record_per_port_and_triplet() {
+        let stdout = r#"{
+  "curl:x64-linux": {
+    "package_name": "curl",
+    "triplet": "x64-linux",
+    "version": "8.8.0",
+    "port_version": 2,
+    "features": ["ssl"],
+    "desc": ["A library for transferring data with URLs"]
+  },
+  "zlib:x64-linux": {
+    "package_name": "zlib",
+    "triplet": "x64-linux",
+    "version": "1.3.1",
+    "port_version": 0,
+    "features": [],
+    "desc": ["A compression library"]
+  },
+  "zlib:x64-windows": {
+    "package_name": "zlib",
+    "triplet": "x64-windows",
+    "version": "1.3.1",
+    "port_version": 0,
+    "features": [],
+    "desc": ["A compression library"]
+  }
+}"#;
+
+        let records = build_records(parse_ports(stdout).unwrap());
+
+        let rows: Vec<(&str, &str, Option<&str>)> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.name.as_str(),
+                    record.current_version.as_str(),
+                    record.source.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("curl", "8.8.0#2", Some("x64-linux")),
+                ("zlib", "1.3.1", Some("x64-linux")),
+                ("zlib", "1.3.1", Some("x64-windows")),
+            ]
+        );
+        assert!(records
+            .iter()
+            .all(|record| record.status == PackageStatus::Unknown));
+    }
+
+    #[test]
+    fn vcpkg_empty_output_has_no_ports() {
+        assert!(parse_ports("").unwrap().is_empty());
+        assert!(parse_ports("{}").unwrap().is_empty());
+    }
+}
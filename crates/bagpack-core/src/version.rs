@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+/// Severity of an available update, from the first differing `major.minor.patch` component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateKind {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl UpdateKind {
+    /// Returns the serialized (snake_case) name of the kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateKind::Major => "major",
+            UpdateKind::Minor => "minor",
+            UpdateKind::Patch => "patch",
+        }
+    }
+}
+
+/// Number of available updates per [`UpdateKind`] across a snapshot.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateDebt {
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+}
+
+impl UpdateDebt {
+    /// Adds one update of the given kind.
+    pub(crate) fn add(&mut self, kind: UpdateKind) {
+        match kind {
+            UpdateKind::Major => self.major += 1,
+            UpdateKind::Minor => self.minor += 1,
+            UpdateKind::Patch => self.patch += 1,
+        }
+    }
+
+    /// Returns the number of updates of any kind.
+    pub fn total(&self) -> usize {
+        self.major + self.minor + self.patch
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Number(u64),
+    Text(&'a str),
+}
+
+/// Normalizes a manager-reported version for display and comparison.
+///
+/// Surrounding whitespace, a leading `v` (`v5.5.2`), and a `N:` epoch prefix (`1:2.3.4-1`) are
+/// removed; [`compare_versions`] still compares the epoch first. Revision suffixes such as
+/// Homebrew's `_1` are kept, sorting as a trailing component after the release they revise.
+/// Records keep the raw string in `current_version`.
+pub fn normalize_version(raw: &str) -> String {
+    normalized(raw).to_string()
+}
+
+fn normalized(raw: &str) -> &str {
+    let (_, version) = split_epoch(raw);
+    match version.strip_prefix(['v', 'V']) {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+        _ => version,
+    }
+}
+
+/// Splits a `N:` epoch prefix (as used by pacman, dnf, and Debian) from the trimmed version; a
+/// version without one has epoch `0`.
+fn split_epoch(raw: &str) -> (u64, &str) {
+    let version = raw.trim();
+    match version.split_once(':') {
+        Some((epoch, rest)) if !epoch.is_empty() && epoch.bytes().all(|b| b.is_ascii_digit()) => {
+            (epoch.parse().unwrap_or(u64::MAX), rest)
+        }
+        _ => (0, version),
+    }
+}
+
+/// Compares two version strings by epoch, then component by component after
+/// [`normalize_version`].
+///
+/// A higher epoch always wins (`2:1.0-1 > 1:2.0-1`), since packagers bump it precisely when the
+/// release numbers go backwards.
+///
+/// Numeric runs compare by value and text runs lexically; `.`, `-`, and `_` only separate
+/// components and build metadata after `+` is ignored. A trailing text component marks a
+/// pre-release, so `1.0rc1 < 1.0 < 1.0.1`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let epochs = split_epoch(a).0.cmp(&split_epoch(b).0);
+    if epochs != Ordering::Equal {
+        return epochs;
+    }
+    let (a, b) = (tokens(a), tokens(b));
+
+    for index in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(index), b.get(index)) {
+            (Some(Token::Number(x)), Some(Token::Number(y))) => x.cmp(y),
+            (Some(Token::Text(x)), Some(Token::Text(y))) => x.cmp(y),
+            (Some(Token::Number(_)), Some(Token::Text(_))) => Ordering::Greater,
+            (Some(Token::Text(_)), Some(Token::Number(_))) => Ordering::Less,
+            (Some(Token::Number(_)), None) => Ordering::Greater,
+            (Some(Token::Text(_)), None) => Ordering::Less,
+            (None, Some(Token::Number(_))) => Ordering::Less,
+            (None, Some(Token::Text(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Classifies moving from `current` to `latest`, or `None` when `latest` is not newer.
+///
+/// Updates that only change pre-release or revision suffixes count as patch updates, and an
+/// epoch bump counts as a major update.
+pub fn update_kind(current: &str, latest: &str) -> Option<UpdateKind> {
+    if compare_versions(latest, current) != Ordering::Greater {
+        return None;
+    }
+    if split_epoch(latest).0 != split_epoch(current).0 {
+        return Some(UpdateKind::Major);
+    }
+
+    let release = |version| -> Vec<u64> {
+        tokens(version)
+            .into_iter()
+            .map_while(|token| match token {
+                Token::Number(value) => Some(value),
+                Token::Text(_) => None,
+            })
+            .collect()
+    };
+    let (current, latest) = (release(current), release(latest));
+
+    let differs_at = (0..3).find(|&index| {
+        current.get(index).copied().unwrap_or(0) != latest.get(index).copied().unwrap_or(0)
+    });
+
+    Some(match differs_at {
+        Some(0) => UpdateKind::Major,
+        Some(1) => UpdateKind::Minor,
+        _ => UpdateKind::Patch,
+    })
+}
+
+fn tokens(version: &str) -> Vec<Token<'_>> {
+    let version = normalized(version);
+    let version = version
+        .split_once('+')
+        .map_or(version, |(release, _)| release);
+
+    let mut tokens = Vec::new();
+    for part in version.split(['.', '-', '_']) {
+        let mut rest = part;
+        while let Some(first) = rest.chars().next() {
+            let is_digit = first.is_ascii_digit();
+            let end = rest
+                .find(|c: char| c.is_ascii_digit() != is_digit)
+                .unwrap_or(rest.len());
+            let (run, tail) = rest.split_at(end);
+            tokens.push(if is_digit {
+                Token::Number(run.parse().unwrap_or(u64::MAX))
+            } else {
+                Token::Text(run)
+            });
+            rest = tail;
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_versions, normalize_version, update_kind, UpdateKind};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn compares_numeric_components_by_value() {
+        assert_eq!(compare_versions("1.10.0", "1.9.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.24.5", "1.24.5"), Ordering::Equal);
+        assert_eq!(compare_versions("3.11.5", "3.11.6"), Ordering::Less);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn pre_releases_sort_before_releases() {
+        assert_eq!(compare_versions("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0-beta.2", "2.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("2.0.0-beta.2", "2.0.0-beta.10"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("1.2.3+build.5", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn classifies_update_kinds() {
+        assert_eq!(update_kind("1.24.5", "2.0.0"), Some(UpdateKind::Major));
+        assert_eq!(update_kind("5.5.2", "5.6.3"), Some(UpdateKind::Minor));
+        assert_eq!(update_kind("1.24.5", "1.24.6"), Some(UpdateKind::Patch));
+        assert_eq!(update_kind("2.0", "2.0.1"), Some(UpdateKind::Patch));
+        assert_eq!(update_kind("1.0rc1", "1.0"), Some(UpdateKind::Patch));
+        assert_eq!(update_kind("1.24.6", "1.24.5"), None);
+        assert_eq!(update_kind("1.24.5", "1.24.5"), None);
+    }
+
+    #[test]
+    fn normalize_strips_prefix_and_epoch() {
+        assert_eq!(normalize_version(" v5.5.2\n"), "5.5.2");
+        assert_eq!(normalize_version("1:2.3.4-1"), "2.3.4-1");
+        assert_eq!(normalize_version("vim-9.1"), "vim-9.1");
+        assert_eq!(compare_versions("v5.5.2", "5.5.2"), Ordering::Equal);
+        assert_eq!(update_kind("v5.5.2", "5.6.3"), Some(UpdateKind::Minor));
+    }
+
+    #[test]
+    fn epoch_is_compared_before_the_release() {
+        assert_eq!(compare_versions("2:1.0-1", "1:2.0-1"), Ordering::Greater);
+        assert_eq!(compare_versions("1:1.0-1", "2.0-1"), Ordering::Greater);
+        assert_eq!(compare_versions("0:2.0-1", "2.0-1"), Ordering::Equal);
+        assert_eq!(update_kind("1:2.0-1", "2:1.0-1"), Some(UpdateKind::Major));
+        assert_eq!(update_kind("2:1.0-1", "1:2.0-1"), None);
+    }
+
+    #[test]
+    fn revision_suffix_sorts_after_its_release() {
+        assert_eq!(normalize_version("1.24.5_1"), "1.24.5_1");
+        assert_eq!(compare_versions("1.24.5_1", "1.24.5"), Ordering::Greater);
+        assert_eq!(compare_versions("1.24.5_1", "1.24.5_2"), Ordering::Less);
+        assert_eq!(update_kind("1.24.5_1", "1.24.6"), Some(UpdateKind::Patch));
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{collect_inventory_with, CollectInventoryOptions, CollectionSummary, SnapshotSink};
+
+/// Handle to a background collection loop started by [`watch`].
+///
+/// Dropping the handle stops the loop as well; call [`WatchHandle::stop`] to do so explicitly.
+#[derive(Debug)]
+pub struct WatchHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Stops the loop and waits for the background thread to exit.
+    ///
+    /// A collection already in progress finishes (and reports) before the thread exits.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // Dropping the sender wakes the loop immediately.
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Optional behaviour for [`watch`]; the default just passes every summary to the callback.
+#[derive(Default)]
+pub struct WatchOptions {
+    /// Receives every summary before the callback does.
+    pub sink: Option<Box<dyn SnapshotSink + Send>>,
+    /// Drops a summary whose [`content_hash`](crate::InventorySnapshot::content_hash) matches
+    /// the previous one instead of publishing it or passing it on.
+    pub skip_unchanged: bool,
+}
+
+/// Collects immediately and then every `interval` on a background thread, publishing each
+/// summary to the [`WatchOptions::sink`] (if any) and then passing it to `on_update`.
+///
+/// A failed publish is logged and does not stop the loop.
+pub fn watch(
+    options: CollectInventoryOptions,
+    interval: Duration,
+    watch_options: WatchOptions,
+    mut on_update: impl FnMut(CollectionSummary) + Send + 'static,
+) -> WatchHandle {
+    let (stop, stopped) = mpsc::channel::<()>();
+
+    let mut last_hash = None;
+    let thread = thread::spawn(move || loop {
+        let summary = collect_inventory_with(&options);
+        let hash = summary.snapshot.content_hash();
+        if !(watch_options.skip_unchanged && last_hash == Some(hash)) {
+            last_hash = Some(hash);
+            if let Some(sink) = &watch_options.sink {
+                if let Err(err) = sink.publish(&summary) {
+                    log::warn!("failed to publish summary: {err}");
+                }
+            }
+            on_update(summary);
+        }
+
+        match stopped.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    });
+
+    WatchHandle {
+        stop: Some(stop),
+        thread: Some(thread),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{watch, WatchOptions};
+    use crate::{CollectInventoryOptions, CollectionSummary, SinkError, SnapshotSink};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Duration;
+
+    struct RecordingSink(Arc<Mutex<Vec<CollectionSummary>>>);
+
+    impl SnapshotSink for RecordingSink {
+        fn publish(&self, summary: &CollectionSummary) -> Result<(), SinkError> {
+            self.0.lock().unwrap().push(summary.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn watch_ticks_until_stopped() {
+        let options = CollectInventoryOptions {
+            managers: Vec::new(),
+            ..CollectInventoryOptions::default()
+        };
+        let (ticks, received) = mpsc::channel();
+
+        let handle = watch(
+            options,
+            Duration::from_millis(10),
+            WatchOptions::default(),
+            move |summary| {
+                let _ = ticks.send(summary);
+            },
+        );
+
+        for _ in 0..2 {
+            let summary = received.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert!(summary.snapshot.generated_at.is_some());
+        }
+        handle.stop();
+
+        // The callback (and its sender) is dropped once the thread has been joined.
+        while received.try_recv().is_ok() {}
+        assert!(matches!(
+            received.try_recv(),
+            Err(mpsc::TryRecvError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn watch_publishes_each_summary_to_sink() {
+        let options = CollectInventoryOptions {
+            managers: Vec::new(),
+            ..CollectInventoryOptions::default()
+        };
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let (ticks, received) = mpsc::channel();
+
+        let sink = RecordingSink(Arc::clone(&published));
+        let handle = watch(
+            options,
+            Duration::from_secs(60),
+            WatchOptions {
+                sink: Some(Box::new(sink)),
+                ..WatchOptions::default()
+            },
+            move |summary| {
+                let _ = ticks.send(summary);
+            },
+        );
+        let summary = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        handle.stop();
+
+        assert_eq!(*published.lock().unwrap(), vec![summary]);
+    }
+
+    #[test]
+    fn watch_skips_unchanged_summaries() {
+        let options = CollectInventoryOptions {
+            managers: Vec::new(),
+            ..CollectInventoryOptions::default()
+        };
+        let (ticks, received) = mpsc::channel();
+
+        let handle = watch(
+            options,
+            Duration::from_millis(10),
+            WatchOptions {
+                skip_unchanged: true,
+                ..WatchOptions::default()
+            },
+            move |summary| {
+                let _ = ticks.send(summary);
+            },
+        );
+
+        received.recv_timeout(Duration::from_secs(5)).unwrap();
+        // Every later scan sees the same empty inventory.
+        assert!(received.recv_timeout(Duration::from_millis(200)).is_err());
+        handle.stop();
+    }
+}
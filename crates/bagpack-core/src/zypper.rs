@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::{
+    ensure_success, run_command, CollectInventoryOptions, CollectionError, PackageManager,
+    PackageRecord, PackageStatus, UNKNOWN_VERSION,
+};
+
+pub(crate) fn collect_zypper(
+    options: &CollectInventoryOptions,
+) -> Result<Vec<PackageRecord>, CollectionError> {
+    // `--details` is needed for the `edition` (version-release) attribute.
+    let search_output = run_command(
+        options,
+        "zypper",
+        &["--xmlout", "search", "--installed-only", "--details"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&search_output, "zypper --xmlout search --installed-only")?;
+
+    let installed = parse_installed(&search_output.stdout)?;
+    if installed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let updates_output = run_command(
+        options,
+        "zypper",
+        &["--xmlout", "list-updates"],
+        None::<&[i32]>,
+    )?;
+    ensure_success(&updates_output, "zypper --xmlout list-updates")?;
+
+    let updates = parse_updates(&updates_output.stdout)?;
+
+    let packages = installed
+        .into_iter()
+        .map(|(name, current_version)| {
+            let latest_version = updates.get(&name).cloned();
+            let status = if latest_version.is_some() {
+                PackageStatus::Outdated
+            } else {
+                PackageStatus::Current
+            };
+
+            PackageRecord {
+                name,
+                current_version,
+                latest_version,
+                installed_at: None,
+                status,
+                manager: PackageManager::Zypper,
+                pinned: false,
+                dependency: false,
+                linked: false,
+                source: None,
+                homepage: None,
+                deprecated: None,
+                provenance: None,
+                download_size: None,
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// Reads `(name, edition)` pairs from `<solvable>` elements of `zypper --xmlout search`.
+///
+/// With `--details` a package is listed once per repository that carries the installed
+/// version, so only the first row per name is kept. Patterns, patches and other non-package
+/// kinds are skipped.
+fn parse_installed(xml: &str) -> Result<Vec<(String, String)>, quick_xml::Error> {
+    let mut installed: Vec<(String, String)> = Vec::new();
+    for attributes in elements(xml, b"solvable")? {
+        if attributes.get("kind").is_some_and(|kind| kind != "package")
+            || attributes
+                .get("status")
+                .is_some_and(|status| status != "installed")
+        {
+            continue;
+        }
+        let Some(name) = attributes.get("name") else {
+            continue;
+        };
+        if installed.iter().any(|(existing, _)| existing == name) {
+            continue;
+        }
+        let version = attributes
+            .get("edition")
+            .cloned()
+            .unwrap_or_else(|| UNKNOWN_VERSION.to_string());
+        installed.push((name.clone(), version));
+    }
+    Ok(installed)
+}
+
+/// Maps package names to the `edition` offered by `<update>` elements of
+/// `zypper --xmlout list-updates`.
+fn parse_updates(xml: &str) -> Result<HashMap<String, String>, quick_xml::Error> {
+    Ok(elements(xml, b"update")?
+        .into_iter()
+        .filter(|attributes| attributes.get("kind").is_none_or(|kind| kind == "package"))
+        .filter_map(|mut attributes| {
+            Some((attributes.remove("name")?, attributes.remove("edition")?))
+        })
+        .collect())
+}
+
+/// Collects the attributes of every element called `tag`, whether self-closing or not.
+fn elements(xml: &str, tag: &[u8]) -> Result<Vec<HashMap<String, String>>, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut found = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element) if element.name().as_ref() == tag => {
+                let mut attributes = HashMap::new();
+                for attribute in element.attributes() {
+                    let attribute = attribute?;
+                    attributes.insert(
+                        String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                        attribute.unescape_value()?.into_owned(),
+                    );
+                }
+                found.push(attributes);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_installed, parse_updates};
+
+    #[test]
+    fn zypper_search_keeps_installed_packages_once() {
+        let xml = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<search-result version="0.0">
+<solvable-list>
+<solvable status="installed" name="vim" kind="package" edition="9.1.0330-1.1" arch="x86_64" repository="(System Packages)"/>
+<solvable status="installed" name="vim" kind="package" edition="9.1.0330-1.1" arch="x86_64" repository="openSUSE-Tumbleweed-Oss"/>
+<solvable status="installed" name="zypper" kind="package" edition="1.14.73-1.1" arch="x86_64" repository="(System Packages)"/>
+<solvable status="installed" name="base" kind="pattern" edition="20200505-48.1" arch="x86_64" repository="(System Packages)"/>
+</solvable-list>
+</search-result>
+</stream>"#;
+
+        assert_eq!(
+            parse_installed(xml).unwrap(),
+            vec![
+                ("vim".to_string(), "9.1.0330-1.1".to_string()),
+                ("zypper".to_string(), "1.14.73-1.1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn zypper_list_updates_maps_new_editions() {
+        let xml = r#"<?xml version='1.0'?>
+<stream>
+<update-status version="0.6">
+<update-list>
+<update kind="package" name="vim" edition="9.1.0400-1.1" arch="x86_64" edition-old="9.1.0330-1.1">
+<summary>Vi IMproved</summary>
+<description>Vim &amp; friends</description>
+<source url="http://download.opensuse.org/tumbleweed/repo/oss" alias="repo-oss"/>
+</update>
+</update-list>
+</update-status>
+</stream>"#;
+
+        let updates = parse_updates(xml).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates["vim"], "9.1.0400-1.1");
+        assert!(parse_updates("<stream><update-status/></stream>")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,814 @@
+#![cfg(unix)]
+
+mod common;
+
+use bagpack_core::{
+    collect_incremental, collect_inventory_strict, collect_inventory_with,
+    collect_inventory_with_collectors, collect_streaming, detect_available_managers_with,
+    detect_python_environments_with, CollectionError, Collector, PackageManager, PackageRecord,
+    PackageStatus,
+};
+use common::{fake_bin, options_for, FAKE_BREW, FAKE_NPM, FAKE_PIP};
+
+#[test]
+fn collect_custom_order_matches_options() {
+    let bin = fake_bin(&[("brew", FAKE_BREW), ("npm", FAKE_NPM), ("pip", FAKE_PIP)]);
+    let options = options_for(
+        &bin,
+        &[
+            PackageManager::Pip,
+            PackageManager::Brew,
+            PackageManager::Npm,
+        ],
+    );
+
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let order: Vec<(PackageManager, &str)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.manager.clone(), record.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (PackageManager::Pip, "requests"),
+            (PackageManager::Brew, "wget"),
+            (PackageManager::Brew, "jq"),
+            (PackageManager::Npm, "typescript"),
+        ]
+    );
+}
+
+#[test]
+fn strict_collection_returns_snapshot_when_every_manager_succeeds() {
+    let bin = fake_bin(&[("brew", FAKE_BREW), ("npm", FAKE_NPM)]);
+
+    let snapshot = collect_inventory_strict(&options_for(
+        &bin,
+        &[PackageManager::Brew, PackageManager::Npm],
+    ))
+    .unwrap();
+
+    assert_eq!(snapshot.packages.len(), 3);
+}
+
+#[test]
+fn strict_collection_fails_with_warnings_on_partial_failure() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    let warnings = collect_inventory_strict(&options_for(
+        &bin,
+        &[PackageManager::Brew, PackageManager::Npm],
+    ))
+    .unwrap_err();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].manager, PackageManager::Npm);
+}
+
+#[test]
+fn cancelling_stops_the_running_command_and_skips_later_managers() {
+    let marker = tempfile::tempdir().unwrap();
+    let npm = format!(": > '{}/npm-ran'", marker.path().display());
+    let bin = fake_bin(&[("brew", "exec /bin/sleep 10"), ("npm", &npm)]);
+    let mut options = options_for(&bin, &[PackageManager::Brew, PackageManager::Npm]);
+    options.max_concurrency = 1;
+
+    let cancel = options.cancel.clone();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        cancel.cancel();
+    });
+    let started = std::time::Instant::now();
+    let summary = collect_inventory_with(&options);
+    canceller.join().unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    assert!(summary.cancelled);
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert!(summary.attempted_managers.is_empty());
+    assert!(!marker.path().join("npm-ran").exists());
+}
+
+#[test]
+fn cancelling_during_npm_deprecations_stops_the_lookups() {
+    let npm = r#"case "$1" in
+  ls) printf '{"dependencies":{"eslint":{"version":"8.57.0"},"typescript":{"version":"5.5.2"}}}' ;;
+  outdated) printf '{}' ;;
+  view) : > "${0%/*}/viewed-$2"; exec /bin/sleep 10 ;;
+esac"#;
+    let bin = fake_bin(&[("npm", npm)]);
+    let mut options = options_for(&bin, &[PackageManager::Npm]);
+    options.deprecations = true;
+
+    let cancel = options.cancel.clone();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        cancel.cancel();
+    });
+    let started = std::time::Instant::now();
+    let summary = collect_inventory_with(&options);
+    canceller.join().unwrap();
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    assert!(summary.cancelled);
+    assert!(summary.snapshot.packages.is_empty());
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert!(bin.path().join("viewed-eslint@8.57.0").exists());
+    assert!(!bin.path().join("viewed-typescript@5.5.2").exists());
+}
+
+#[test]
+fn rustup_check_failure_keeps_records_with_unknown_status() {
+    let rustup = r#"case "$1" in
+  toolchain) printf 'stable-x86_64-unknown-linux-gnu (active, default)\n' ;;
+  component) printf 'cargo-x86_64-unknown-linux-gnu\n' ;;
+  check) echo 'error: could not download file' >&2; exit 1 ;;
+esac"#;
+    let bin = fake_bin(&[("rustup", rustup)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Rustup]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let records: Vec<(&str, PackageStatus)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.name.as_str(), record.status))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            ("stable-x86_64-unknown-linux-gnu", PackageStatus::Unknown),
+            ("cargo-x86_64-unknown-linux-gnu", PackageStatus::Unknown),
+        ]
+    );
+}
+
+#[test]
+fn collect_repeated_manager_runs_once() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+    let options = options_for(&bin, &[PackageManager::Brew, PackageManager::Brew]);
+
+    let summary = collect_inventory_with(&options);
+
+    assert_eq!(summary.snapshot.packages.len(), 2);
+}
+
+#[test]
+fn collect_streaming_delivers_each_record_to_sink() {
+    let bin = fake_bin(&[("brew", FAKE_BREW), ("npm", FAKE_NPM)]);
+    let mut options = options_for(
+        &bin,
+        &[
+            PackageManager::Brew,
+            PackageManager::Npm,
+            PackageManager::Pip,
+        ],
+    );
+    options.ignore = vec![(Some(PackageManager::Brew), "jq".to_string())];
+
+    let mut delivered = Vec::new();
+    let warnings = collect_streaming(&options, |record| {
+        delivered.push((record.manager, record.name));
+    });
+
+    delivered.sort();
+    assert_eq!(
+        delivered,
+        vec![
+            (PackageManager::Brew, "wget".to_string()),
+            (PackageManager::Npm, "typescript".to_string()),
+        ]
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].manager, PackageManager::Pip);
+}
+
+#[test]
+fn max_per_manager_truncates_by_name_with_a_warning() {
+    let bin = fake_bin(&[("brew", FAKE_BREW), ("npm", FAKE_NPM)]);
+    let mut options = options_for(&bin, &[PackageManager::Brew, PackageManager::Npm]);
+    options.max_per_manager = Some(1);
+
+    let summary = collect_inventory_with(&options);
+
+    let order: Vec<(PackageManager, &str)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.manager.clone(), record.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (PackageManager::Brew, "jq"),
+            (PackageManager::Npm, "typescript"),
+        ]
+    );
+    assert_eq!(summary.warnings.len(), 1);
+    assert_eq!(summary.warnings[0].manager, PackageManager::Brew);
+    assert_eq!(
+        summary.warnings[0].message,
+        "showing 1 of 2 packages (max_per_manager)"
+    );
+}
+
+#[test]
+fn collect_incremental_refreshes_only_selected_managers() {
+    let bin = fake_bin(&[("brew", FAKE_BREW), ("npm", FAKE_NPM)]);
+    let mut previous = collect_inventory_with(&options_for(
+        &bin,
+        &[PackageManager::Brew, PackageManager::Npm],
+    ))
+    .snapshot;
+    previous.set_generated_at("2025-01-01T00:00:00Z");
+
+    // brew is gone, so carried-over records can only come from the previous snapshot.
+    let bin = fake_bin(&[(
+        "npm",
+        r#"case "$1" in
+  ls) printf '{"dependencies":{"typescript":{"version":"5.6.3"},"pnpm":{"version":"9.1.0"}}}' ;;
+  outdated) printf '{}' ;;
+esac"#,
+    )]);
+    let summary = collect_incremental(&previous, &options_for(&bin, &[PackageManager::Npm]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.attempted_managers, vec![PackageManager::Npm]);
+    assert_ne!(
+        summary.snapshot.generated_at.as_deref(),
+        Some("2025-01-01T00:00:00Z")
+    );
+    let rows: Vec<(PackageManager, &str, &str)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| {
+            (
+                record.manager.clone(),
+                record.name.as_str(),
+                record.current_version.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (PackageManager::Brew, "wget", "1.24.5"),
+            (PackageManager::Brew, "jq", "1.7.1"),
+            (PackageManager::Npm, "pnpm", "9.1.0"),
+            (PackageManager::Npm, "typescript", "5.6.3"),
+        ]
+    );
+}
+
+struct MockCollector {
+    name: &'static str,
+    packages: &'static [&'static str],
+}
+
+impl Collector for MockCollector {
+    fn manager(&self) -> PackageManager {
+        PackageManager::Other(self.name.to_string())
+    }
+
+    fn collect(&self) -> Result<Vec<PackageRecord>, CollectionError> {
+        if self.packages.is_empty() {
+            return Err(CollectionError::NoCollector(self.manager()));
+        }
+        Ok(self
+            .packages
+            .iter()
+            .map(|name| {
+                PackageRecord::builder(*name, self.manager())
+                    .current_version("1.0.0")
+                    .build()
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn custom_collectors_run_alongside_built_ins() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+    let collectors: Vec<Box<dyn Collector>> = vec![
+        Box::new(MockCollector {
+            name: "acme-pkg",
+            packages: &["widget", "gadget"],
+        }),
+        Box::new(MockCollector {
+            name: "broken-pkg",
+            packages: &[],
+        }),
+    ];
+
+    let summary =
+        collect_inventory_with_collectors(&options_for(&bin, &[PackageManager::Brew]), &collectors);
+
+    let acme = PackageManager::Other("acme-pkg".into());
+    let broken = PackageManager::Other("broken-pkg".into());
+    let order: Vec<(PackageManager, &str)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.manager.clone(), record.name.as_str()))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            (PackageManager::Brew, "wget"),
+            (PackageManager::Brew, "jq"),
+            (acme.clone(), "widget"),
+            (acme.clone(), "gadget"),
+        ]
+    );
+    assert_eq!(
+        summary.attempted_managers,
+        vec![PackageManager::Brew, acme, broken.clone()]
+    );
+    assert_eq!(summary.warnings.len(), 1);
+    assert_eq!(summary.warnings[0].manager, broken);
+}
+
+#[test]
+fn npm_linked_package_skips_outdated_detection() {
+    let npm = r#"case "$1" in
+  ls) printf '{"dependencies":{"my-cli":{"version":"0.0.0-dev","resolved":"file:../../home/dev/my-cli"},"typescript":{"version":"5.5.2"}}}' ;;
+  outdated) printf '{"my-cli":{"current":"0.0.0-dev","latest":"1.2.0"},"typescript":{"current":"5.5.2","latest":"5.6.3"}}'; exit 1 ;;
+esac"#;
+    let bin = fake_bin(&[("npm", npm)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Npm]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let packages = &summary.snapshot.packages;
+    assert_eq!(packages[0].name, "my-cli");
+    assert!(packages[0].linked);
+    assert_eq!(packages[0].status, PackageStatus::Unknown);
+    assert_eq!(packages[0].latest_version, None);
+    assert!(!packages[1].linked);
+    assert_eq!(packages[1].status, PackageStatus::Outdated);
+}
+
+#[test]
+fn lossy_utf8_replaces_invalid_bytes_instead_of_failing() {
+    let pip = r#"case "$*" in
+  *--outdated*) printf '[]' ;;
+  *) printf '[{"name":"requests","version":"2.32.3","summary":"caf\351"}]' ;;
+esac"#;
+    let bin = fake_bin(&[("pip", pip)]);
+    let mut options = options_for(&bin, &[PackageManager::Pip]);
+
+    let strict = collect_inventory_with(&options);
+    assert_eq!(strict.warnings.len(), 1);
+    assert!(strict.snapshot.packages.is_empty());
+
+    options.lossy_utf8 = true;
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.snapshot.packages[0].name, "requests");
+}
+
+#[test]
+fn pip_tags_scopes_and_prefers_user_site_duplicates() {
+    let pip = r#"case "$*" in
+  *--outdated*) printf '[{"name":"black","version":"24.1.0","latest_version":"24.8.0"}]' ;;
+  *--user*) printf '[{"name":"black","version":"24.1.0"},{"name":"httpie","version":"3.2.3"}]' ;;
+  *) printf '[{"name":"black","version":"24.1.0"},{"name":"requests","version":"2.32.3"},{"name":"httpie","version":"3.2.3"}]' ;;
+esac"#;
+    let bin = fake_bin(&[("pip", pip)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Pip]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let scopes: Vec<(&str, Option<&str>, PackageStatus)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| {
+            (
+                record.name.as_str(),
+                record.source.as_deref(),
+                record.status,
+            )
+        })
+        .collect();
+    assert_eq!(
+        scopes,
+        vec![
+            ("requests", Some("system"), PackageStatus::Current),
+            ("black", Some("user"), PackageStatus::Outdated),
+            ("httpie", Some("user"), PackageStatus::Current),
+        ]
+    );
+}
+
+#[test]
+fn pip_without_user_site_reports_system_packages() {
+    let pip = r#"case "$*" in
+  *--outdated*) printf '[]' ;;
+  *--user*) echo 'ERROR: The user site-packages directory is disabled.' >&2; exit 1 ;;
+  *) printf '[{"name":"requests","version":"2.32.3"}]' ;;
+esac"#;
+    let bin = fake_bin(&[("pip", pip)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Pip]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(
+        summary.snapshot.packages[0].source.as_deref(),
+        Some("system")
+    );
+}
+
+#[test]
+fn latest_older_than_installed_is_ahead_not_outdated() {
+    let pip = r#"case "$*" in
+  *--outdated*) printf '[{"name":"requests","version":"2.32.3","latest_version":"2.31.0"},{"name":"black","version":"24.1.0","latest_version":"24.8.0"}]' ;;
+  *--user*) printf '[]' ;;
+  *) printf '[{"name":"requests","version":"2.32.3"},{"name":"black","version":"24.1.0"}]' ;;
+esac"#;
+    let bin = fake_bin(&[("pip", pip)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Pip]));
+
+    let statuses: Vec<(&str, PackageStatus)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.name.as_str(), record.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("requests", PackageStatus::Ahead),
+            ("black", PackageStatus::Outdated),
+        ]
+    );
+    assert_eq!(summary.snapshot.outdated_count(), 1);
+}
+
+#[test]
+fn command_retries_report_only_the_final_failure() {
+    let pip = r#"echo 'read timed out' >&2; exit 1"#;
+    let bin = fake_bin(&[("pip", pip)]);
+    let mut options = options_for(&bin, &[PackageManager::Pip]);
+    options.command_retries = 2;
+
+    let summary = collect_inventory_with(&options);
+
+    assert_eq!(summary.warnings.len(), 1);
+    assert!(
+        summary.warnings[0]
+            .message
+            .starts_with("failed after 3 attempts: pip list --format=json exited with status"),
+        "{}",
+        summary.warnings[0].message
+    );
+}
+
+#[test]
+fn npm_ls_exit_one_with_a_valid_tree_still_collects() {
+    let npm = r#"case "$1" in
+  ls) printf '{"problems":["missing peer dep"],"dependencies":{"typescript":{"version":"5.5.2"}}}'; echo 'npm ERR! peer dep missing' >&2; exit 1 ;;
+  outdated) printf '{}' ;;
+esac"#;
+    let bin = fake_bin(&[("npm", npm)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Npm]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.snapshot.packages[0].name, "typescript");
+
+    let bin = fake_bin(&[("npm", "echo 'npm ERR! broken' >&2; exit 1")]);
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Npm]));
+    assert_eq!(summary.warnings.len(), 1);
+}
+
+#[test]
+fn npm_depth_collects_nested_dependencies_once() {
+    let npm = r#"case "$*" in
+  "ls -g --depth=2 --json") printf '{"dependencies":{"eslint":{"version":"9.1.0","dependencies":{"chalk":{"version":"4.1.2","dependencies":{"ansi-styles":{"version":"4.3.0"}}}}},"typescript":{"version":"5.5.2"},"chalk":{"version":"5.3.0"}}}' ;;
+  "outdated -g --json") printf '{}' ;;
+  *) exit 1 ;;
+esac"#;
+    let bin = fake_bin(&[("npm", npm)]);
+    let mut options = options_for(&bin, &[PackageManager::Npm]);
+    options.npm_depth = 2;
+
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let rows: Vec<(&str, &str, bool)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| {
+            (
+                record.name.as_str(),
+                record.current_version.as_str(),
+                record.dependency,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("chalk", "5.3.0", false),
+            ("eslint", "9.1.0", false),
+            ("typescript", "5.5.2", false),
+            ("ansi-styles", "4.3.0", true),
+        ]
+    );
+    assert_eq!(summary.snapshot.packages[3].status, PackageStatus::Unknown);
+}
+
+#[test]
+fn commands_run_from_the_configured_working_dir() {
+    let pip = r#"case "$*" in
+  *--outdated*) printf '[]' ;;
+  *) printf '[{"name":"%s","version":"1.0.0"}]' "${PWD##*/}" ;;
+esac"#;
+    let bin = fake_bin(&[("pip", pip)]);
+    let project = tempfile::tempdir().unwrap();
+    let mut options = options_for(&bin, &[PackageManager::Pip]);
+    options.working_dir = Some(project.path().to_path_buf());
+
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(
+        summary.snapshot.packages[0].name,
+        project.path().file_name().unwrap().to_str().unwrap()
+    );
+}
+
+#[test]
+fn allowed_exit_code_override_treats_failure_as_success() {
+    let brew = r#"case "$1" in
+  list) printf 'wget 1.24.5\n'; exit 3 ;;
+  outdated) printf '{"formulae":[]}' ;;
+esac"#;
+    let bin = fake_bin(&[("brew", brew)]);
+    let mut options = options_for(&bin, &[PackageManager::Brew]);
+
+    let failing = collect_inventory_with(&options);
+    assert_eq!(failing.warnings.len(), 1);
+
+    options
+        .allowed_exit_codes
+        .insert("brew list --versions".to_string(), vec![3]);
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.snapshot.packages[0].name, "wget");
+}
+
+#[test]
+fn brew_runs_without_auto_update_or_analytics_unless_overridden() {
+    let brew = r#"case "$1" in
+  list) printf 'env-%s-%s 1.0.0\n' "$HOMEBREW_NO_AUTO_UPDATE" "$HOMEBREW_NO_ANALYTICS" ;;
+  outdated) printf '{"formulae":[]}' ;;
+esac"#;
+    let bin = fake_bin(&[("brew", brew)]);
+    let mut options = options_for(&bin, &[PackageManager::Brew]);
+
+    let summary = collect_inventory_with(&options);
+    assert_eq!(summary.snapshot.packages[0].name, "env-1-1");
+
+    options
+        .env
+        .insert("HOMEBREW_NO_ANALYTICS".to_string(), String::new());
+    let summary = collect_inventory_with(&options);
+    assert_eq!(summary.snapshot.packages[0].name, "env-1-");
+}
+
+#[test]
+fn dnf_runs_with_c_locale_unless_overridden() {
+    let dnf = r#"case "$1" in
+  list) printf 'lc-%s-%s.x86_64  1.0-1.fc40  @fedora\n' "$LC_ALL" "$LANG" ;;
+  check-update) [ "$LC_ALL" = C ] || exit 1 ;;
+esac"#;
+    let bin = fake_bin(&[("dnf", dnf)]);
+    let mut options = options_for(&bin, &[PackageManager::Dnf]);
+
+    let summary = collect_inventory_with(&options);
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.snapshot.packages[0].name, "lc-C-C");
+
+    options
+        .env
+        .insert("LANG".to_string(), "de_DE.UTF-8".to_string());
+    let summary = collect_inventory_with(&options);
+    assert_eq!(summary.snapshot.packages[0].name, "lc-C-de_DE.UTF-8");
+}
+
+#[test]
+fn brew_formula_missing_from_leaves_is_dependency() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Brew]));
+
+    let packages = &summary.snapshot.packages;
+    assert_eq!(
+        (packages[0].name.as_str(), packages[0].dependency),
+        ("wget", false)
+    );
+    assert_eq!(
+        (packages[1].name.as_str(), packages[1].dependency),
+        ("jq", true)
+    );
+}
+
+#[test]
+fn brew_leaves_failure_keeps_formulae_as_top_level() {
+    let brew = r#"case "$1" in
+  list) printf 'wget 1.24.5\njq 1.7.1\n' ;;
+  leaves) echo 'Error: leaves exploded' >&2; exit 1 ;;
+  outdated) printf '{"formulae":[]}' ;;
+esac"#;
+    let bin = fake_bin(&[("brew", brew)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Brew]));
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    let packages: Vec<(&str, bool)> = summary
+        .snapshot
+        .packages
+        .iter()
+        .map(|record| (record.name.as_str(), record.dependency))
+        .collect();
+    assert_eq!(packages, vec![("wget", false), ("jq", false)]);
+}
+
+#[test]
+fn detect_reports_only_responsive_managers() {
+    let bin = fake_bin(&[
+        ("brew", FAKE_BREW),
+        ("pip", "exit 1"),
+        ("npm", "exec /bin/sleep 10"),
+        ("rustup", "exit 0"),
+    ]);
+    let options = options_for(&bin, &[]);
+
+    let started = std::time::Instant::now();
+    let available = detect_available_managers_with(&options, false);
+
+    assert_eq!(
+        available,
+        vec![PackageManager::Brew, PackageManager::Rustup]
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(8));
+}
+
+#[test]
+fn detect_reuses_cached_result_unless_forced() {
+    let counter = tempfile::NamedTempFile::new().unwrap();
+    let brew = format!("echo probe >> '{}'", counter.path().display());
+    let bin = fake_bin(&[("brew", &brew)]);
+    let options = options_for(&bin, &[]);
+    let probes = || {
+        std::fs::read_to_string(counter.path())
+            .unwrap()
+            .lines()
+            .count()
+    };
+
+    assert_eq!(
+        detect_available_managers_with(&options, false),
+        vec![PackageManager::Brew]
+    );
+    assert_eq!(
+        detect_available_managers_with(&options, false),
+        vec![PackageManager::Brew]
+    );
+    assert_eq!(probes(), 1);
+
+    detect_available_managers_with(&options, true);
+    assert_eq!(probes(), 2);
+}
+
+#[test]
+fn detect_python_environments_finds_each_working_interpreter() {
+    let pyenv_root = tempfile::tempdir().unwrap();
+    let pyenv_bin = pyenv_root.path().join("versions/3.11.9/bin");
+    std::fs::create_dir_all(&pyenv_bin).unwrap();
+    common::write_script(&pyenv_bin, "python3", "echo 'Python 3.11.9'");
+
+    let pyenv = format!("echo '{}'", pyenv_root.path().display());
+    let first = fake_bin(&[("python3", "echo 'Python 3.12.1'"), ("pyenv", &pyenv)]);
+    let second = fake_bin(&[("python3", "echo 'Python 3.9.18'")]);
+    let broken = fake_bin(&[("python3", "exit 1")]);
+    let mut options = options_for(&first, &[]);
+    let path = std::env::join_paths([first.path(), broken.path(), second.path(), first.path()]);
+    options.env.insert(
+        "PATH".to_string(),
+        path.unwrap().to_string_lossy().into_owned(),
+    );
+
+    let pythons = detect_python_environments_with(&options);
+
+    assert_eq!(
+        pythons[..3],
+        [
+            pyenv_bin.join("python3"),
+            first.path().join("python3"),
+            second.path().join("python3"),
+        ]
+    );
+    assert!(!pythons.contains(&broken.path().join("python3")));
+}
+
+#[test]
+fn detect_python_environments_gives_up_on_a_hung_pyenv() {
+    let bin = fake_bin(&[
+        ("python3", "echo 'Python 3.12.1'"),
+        ("pyenv", "exec /bin/sleep 10"),
+    ]);
+    let options = options_for(&bin, &[]);
+
+    let started = std::time::Instant::now();
+    let pythons = detect_python_environments_with(&options);
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(8));
+    assert_eq!(pythons[0], bin.path().join("python3"));
+}
+
+#[test]
+fn record_provenance_names_the_commands_behind_each_record() {
+    let bin = fake_bin(&[("npm", FAKE_NPM)]);
+    let mut options = options_for(&bin, &[PackageManager::Npm]);
+
+    let summary = collect_inventory_with(&options);
+    assert_eq!(summary.snapshot.packages[0].provenance, None);
+    assert!(serde_json::to_string(&summary.snapshot)
+        .unwrap()
+        .contains(r#""provenance":null"#));
+
+    options.record_provenance = true;
+    let summary = collect_inventory_with(&options);
+
+    assert_eq!(
+        summary.snapshot.packages[0].provenance.as_deref(),
+        Some("npm ls -g --depth=0 --json; npm outdated -g --json")
+    );
+}
+
+#[test]
+fn capture_raw_keeps_command_stdout_per_manager() {
+    let bin = fake_bin(&[("npm", FAKE_NPM)]);
+    let mut options = options_for(&bin, &[PackageManager::Npm]);
+
+    let summary = collect_inventory_with(&options);
+    assert!(summary.raw_outputs.is_empty());
+    assert!(!serde_json::to_string(&summary)
+        .unwrap()
+        .contains("raw_outputs"));
+
+    options.capture_raw = true;
+    let summary = collect_inventory_with(&options);
+
+    assert_eq!(
+        summary.raw_outputs[&PackageManager::Npm],
+        vec![
+            r#"{"dependencies":{"typescript":{"version":"5.5.2"}}}"#.to_string(),
+            "{}".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn brew_multiple_installed_versions_use_the_newest() {
+    let brew = r#"case "$1" in
+  list) printf 'python@3.11 3.11.10 3.11.9\n' ;;
+  leaves) printf 'python@3.11\n' ;;
+  outdated) printf '{"formulae":[{"name":"python@3.11","installed_versions":["3.11.10","3.11.9"],"current_version":"3.11.10"}]}' ;;
+esac"#;
+    let bin = fake_bin(&[("brew", brew)]);
+
+    let summary = collect_inventory_with(&options_for(&bin, &[PackageManager::Brew]));
+
+    let python = &summary.snapshot.packages[0];
+    assert_eq!(python.current_version, "3.11.10");
+    assert_eq!(python.status, PackageStatus::Current);
+}
+
+#[test]
+fn durations_cover_successful_and_failed_collectors() {
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    let summary = collect_inventory_with(&options_for(
+        &bin,
+        &[PackageManager::Brew, PackageManager::Npm],
+    ));
+
+    assert_eq!(summary.warnings.len(), 1);
+    assert_eq!(summary.durations.len(), 2);
+    assert!(summary.durations[&PackageManager::Brew] > std::time::Duration::ZERO);
+    assert!(summary.durations.contains_key(&PackageManager::Npm));
+}
@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use bagpack_core::{CollectInventoryOptions, PackageManager};
+use tempfile::TempDir;
+
+/// Fake `brew` printing two formulae: `wget` is outdated and `jq` is only a dependency.
+pub const FAKE_BREW: &str = r#"case "$1" in
+  list) printf 'wget 1.24.5\njq 1.7.1\n' ;;
+  leaves) printf 'wget\n' ;;
+  outdated) printf '{"formulae":[{"name":"wget","installed_versions":["1.24.5"],"current_version":"1.24.6"}]}' ;;
+esac"#;
+
+/// Fake `npm` with a single global package and nothing outdated.
+pub const FAKE_NPM: &str = r#"case "$1" in
+  ls) printf '{"dependencies":{"typescript":{"version":"5.5.2"}}}' ;;
+  outdated) printf '{}' ;;
+esac"#;
+
+/// Fake `pip` with one system-wide package that has a newer release.
+pub const FAKE_PIP: &str = r#"case "$*" in
+  *--outdated*) printf '[{"name":"requests","version":"2.32.3","latest_version":"2.33.0"}]' ;;
+  *--user*) printf '[]' ;;
+  *) printf '[{"name":"requests","version":"2.32.3"}]' ;;
+esac"#;
+
+/// Creates a directory of executable shell scripts standing in for manager CLIs.
+pub fn fake_bin(scripts: &[(&str, &str)]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for (name, body) in scripts {
+        write_script(dir.path(), name, body);
+    }
+    dir
+}
+
+pub fn write_script(dir: &Path, name: &str, body: &str) {
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Options that resolve manager binaries only from `bin`.
+pub fn options_for(bin: &TempDir, managers: &[PackageManager]) -> CollectInventoryOptions {
+    CollectInventoryOptions {
+        managers: managers.to_vec(),
+        env: HashMap::from([("PATH".to_string(), bin.path().display().to_string())]),
+        ..CollectInventoryOptions::default()
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::sync::{Mutex, Once};
+
+use bagpack_core::{collect_inventory_with, CollectInventoryOptions, PackageManager};
+use common::{fake_bin, options_for, FAKE_BREW};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Keeps every log line so the test can inspect what collection emitted.
+struct CapturingLogger {
+    lines: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.lines
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    lines: Mutex::new(Vec::new()),
+};
+
+/// Installs [`LOGGER`] once for all tests in this binary; their lines are interleaved.
+fn capture_logs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+    });
+}
+
+#[test]
+fn collection_logs_commands_and_package_counts() {
+    capture_logs();
+    let bin = fake_bin(&[("brew", FAKE_BREW)]);
+
+    collect_inventory_with(&options_for(&bin, &[PackageManager::Brew]));
+
+    let lines = LOGGER.lines.lock().unwrap();
+    assert!(
+        lines.iter().any(|(level, line)| *level == Level::Debug
+            && line.starts_with("ran `brew list --versions`: exit code Some(0) in ")),
+        "{lines:?}"
+    );
+    assert!(lines.contains(&(Level::Info, "brew: collected 2 packages".to_string())));
+}
+
+#[test]
+fn retried_command_logs_attempts_without_warning() {
+    capture_logs();
+    let bin = fake_bin(&[(
+        "pip",
+        r#"dir=${0%/*}
+case "$*" in
+  *--outdated*) printf '[]' ;;
+  *--user*) printf '[]' ;;
+  *) if [ -e "$dir/failed-twice" ]; then
+       printf '[{"name":"requests","version":"2.32.3"}]'
+     elif [ -e "$dir/failed-once" ]; then
+       : > "$dir/failed-twice"; echo 'read timed out' >&2; exit 1
+     else
+       : > "$dir/failed-once"; echo 'read timed out' >&2; exit 1
+     fi ;;
+esac"#,
+    )]);
+    let options = CollectInventoryOptions {
+        command_retries: 2,
+        capture_raw: true,
+        ..options_for(&bin, &[PackageManager::Pip])
+    };
+
+    let summary = collect_inventory_with(&options);
+
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+    assert_eq!(summary.snapshot.packages.len(), 1);
+    assert_eq!(
+        summary.raw_outputs[&PackageManager::Pip],
+        vec![
+            r#"[{"name":"requests","version":"2.32.3"}]"#.to_string(),
+            "[]".to_string(),
+            "[]".to_string(),
+        ]
+    );
+    let lines = LOGGER.lines.lock().unwrap();
+    assert!(
+        lines.contains(&(
+            Level::Info,
+            "`pip list --format=json` succeeded after 3 attempts".to_string()
+        )),
+        "{lines:?}"
+    );
+    assert!(
+        lines.iter().any(|(level, line)| *level == Level::Debug
+            && line.starts_with("`pip list --format=json` failed (attempt 2 of 3)")),
+        "{lines:?}"
+    );
+}
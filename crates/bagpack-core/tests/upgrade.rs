@@ -0,0 +1,50 @@
+#![cfg(unix)]
+
+mod common;
+
+use bagpack_core::{upgrade_manager, upgrade_plan, CollectionError, PackageManager};
+use common::{fake_bin, options_for, FAKE_PIP};
+
+#[test]
+fn upgrade_plan_enumerates_outdated_pip_packages() {
+    let bin = fake_bin(&[("pip", FAKE_PIP)]);
+    let options = options_for(&bin, &[]);
+
+    assert_eq!(
+        upgrade_plan(PackageManager::Brew, &options).unwrap(),
+        vec![vec!["brew", "upgrade"]]
+    );
+    assert_eq!(
+        upgrade_plan(PackageManager::Npm, &options).unwrap(),
+        vec![vec!["npm", "update", "-g"]]
+    );
+    assert_eq!(
+        upgrade_plan(PackageManager::Pip, &options).unwrap(),
+        vec![vec!["pip", "install", "-U", "requests"]]
+    );
+    assert!(matches!(
+        upgrade_plan(PackageManager::Pacman, &options),
+        Err(CollectionError::UpgradeUnsupported(PackageManager::Pacman))
+    ));
+}
+
+#[test]
+fn upgrade_manager_combines_output_and_reports_failure() {
+    let brew = r#"case "$1" in
+  upgrade) echo '==> Upgrading wget'; echo 'Warning: jq is pinned' >&2 ;;
+esac"#;
+    let npm = r#"echo 'npm ERR! EACCES' >&2; exit 243"#;
+    let bin = fake_bin(&[("brew", brew), ("npm", npm)]);
+    let options = options_for(&bin, &[]);
+
+    let upgraded = upgrade_manager(PackageManager::Brew, &options);
+    assert!(upgraded.success);
+    assert_eq!(
+        upgraded.output,
+        "==> Upgrading wget\nWarning: jq is pinned\n"
+    );
+
+    let failed = upgrade_manager(PackageManager::Npm, &options);
+    assert!(!failed.success);
+    assert!(failed.output.contains("EACCES"), "{}", failed.output);
+}